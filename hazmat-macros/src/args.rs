@@ -0,0 +1,468 @@
+use proc_macro2::Span;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    FnArg, Ident, LitStr, Pat, Path, Token, Type,
+};
+
+/// The arguments that can be passed to `#[hazmat::suit(..)]`.
+#[derive(Default)]
+pub(crate) struct SuitArgs {
+    /// Makes the generated capability `!Send` and `!Sync`.
+    pub(crate) thread_local: Option<Ident>,
+
+    /// Makes a `thread_local` capability `!Send` and `!Sync` with negative impls.
+    pub(crate) thread_local_negative_impls: Option<LitStr>,
+
+    /// Makes the generated capability `!Unpin`.
+    pub(crate) pinned: Option<Ident>,
+
+    /// Generates `#[cfg(test)]` tests of the capability's basic properties.
+    pub(crate) emit_tests: Option<Ident>,
+
+    /// Asserts at compile time that each method gained the expected number of arguments.
+    pub(crate) verify_arity: Option<Ident>,
+
+    /// Forbids using `#[hazmat::suit]` on impls of the trait.
+    pub(crate) require_explicit_cap: Option<Ident>,
+
+    /// Replaces the constructor with an `unsafe fn new_unchecked()`.
+    pub(crate) unsafe_ctor: Option<Ident>,
+
+    /// Makes the capability's constructor public when building documentation.
+    pub(crate) doc_ctor: Option<Ident>,
+
+    /// Enters a `tracing` span in each default method body.
+    pub(crate) log_usage: Option<Ident>,
+
+    /// The name of a safe extension trait to generate.
+    pub(crate) safe_ext: Option<Ident>,
+
+    /// Passes the capability to methods by reference instead of by value.
+    pub(crate) by_ref: Option<Ident>,
+
+    /// Checks for the capability at runtime instead of passing it as an argument.
+    pub(crate) ambient: Option<Ident>,
+
+    /// Makes the generated capability generic over the trait's generic parameters.
+    pub(crate) generic_cap: Option<Ident>,
+
+    /// Where the capability argument is placed in each method's signature.
+    pub(crate) position: Option<Position>,
+
+    /// A type that must be borrowed to construct the capability.
+    pub(crate) mint_via: Option<Type>,
+
+    /// A type that must be borrowed to construct the capability, via a `From` impl.
+    pub(crate) from_seed: Option<Type>,
+
+    /// A function to call whenever the capability is constructed.
+    pub(crate) audit_callback: Option<Path>,
+
+    /// A preset set of traits to derive for the generated capability.
+    preset: Option<Preset>,
+
+    /// Traits to derive for the generated capability.
+    derives: Vec<Path>,
+
+    /// Traits to implement for the generated capability.
+    pub(crate) cap_impls: Vec<Path>,
+
+    /// The label that the generated capability's `Display` impl writes.
+    pub(crate) display: Option<LitStr>,
+
+    /// The only types that may implement the trait.
+    pub(crate) sealed: Vec<Type>,
+
+    /// Suppresses every optional item generated for the capability.
+    pub(crate) cap_default_impls_none: Option<LitStr>,
+
+    /// Asserts at compile time that the capability is zero-sized.
+    pub(crate) cap_size_zero: Option<LitStr>,
+
+    /// The path to use in generated code instead of `::core`.
+    no_std_core: Option<Path>,
+
+    /// An existing type to use as the capability, instead of generating one.
+    pub(crate) cap_ty: Option<Type>,
+
+    /// The module (relative to the crate root) from which the capability must be
+    /// re-exported.
+    pub(crate) cap_in_mod: Option<Path>,
+
+    /// The crate name with which to prefix the hidden module containing the capability.
+    cap_module: Option<Ident>,
+
+    /// The names of the arguments that were given, and whether they only make sense when
+    /// applied to a trait.
+    given: Vec<(Ident, bool)>,
+}
+
+/// Where the capability argument is placed in each method's signature.
+#[derive(Clone, Copy)]
+pub(crate) enum Position {
+    /// After all other arguments.
+    Last,
+    /// Immediately after the receiver (or first, if the method has no receiver).
+    AfterReceiver,
+}
+
+/// A preset set of traits to derive for the generated capability.
+#[derive(Clone, Copy)]
+enum Preset {
+    /// `Debug`, `Clone`, and `Copy`.
+    Standard,
+    /// No derives.
+    Minimal,
+}
+
+impl SuitArgs {
+    /// Returns whether the capability must only be constructed via its constructor.
+    pub(crate) fn restricts_construction(&self) -> bool {
+        self.mint_guard().is_some() || self.audit_callback.is_some() || self.unsafe_ctor.is_some()
+    }
+
+    /// Returns the name of the capability's constructor.
+    pub(crate) fn ctor_name(&self) -> Ident {
+        let name = if self.unsafe_ctor.is_some() {
+            "new_unchecked"
+        } else {
+            "new"
+        };
+        Ident::new(name, Span::call_site())
+    }
+
+    /// Returns the type that must be borrowed to construct the capability, if any.
+    pub(crate) fn mint_guard(&self) -> Option<&Type> {
+        self.mint_via.as_ref().or(self.from_seed.as_ref())
+    }
+
+    /// Returns the path to `core` that generated code should use.
+    pub(crate) fn core_path(&self) -> Path {
+        self.no_std_core
+            .clone()
+            .unwrap_or_else(|| parse_quote!(::core))
+    }
+
+    /// Returns the traits to derive for the generated capability.
+    pub(crate) fn cap_derives(&self) -> Vec<Path> {
+        let core = self.core_path();
+        let preset = match self.preset {
+            Some(Preset::Standard) => vec![
+                parse_quote!(#core::fmt::Debug),
+                parse_quote!(#core::clone::Clone),
+                parse_quote!(#core::marker::Copy),
+            ],
+            Some(Preset::Minimal) | None => vec![],
+        };
+        preset
+            .into_iter()
+            .chain(self.derives.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the name of the hidden module that contains the capability for the trait
+    /// `trait_name`, if the capability should be placed in one.
+    pub(crate) fn cap_module(&self, trait_name: &Ident) -> Option<Ident> {
+        self.cap_module.as_ref().map(|prefix| {
+            Ident::new(
+                &format!("__hazmat_{}_{}", prefix, trait_name),
+                trait_name.span(),
+            )
+        })
+    }
+
+    /// Returns the index at which the capability argument should be inserted into the
+    /// given method inputs.
+    pub(crate) fn cap_index(&self, inputs: &Punctuated<FnArg, Token![,]>) -> usize {
+        match self.position.unwrap_or(Position::Last) {
+            Position::Last => inputs.len(),
+            Position::AfterReceiver => match inputs.first() {
+                Some(FnArg::Receiver(_)) => 1,
+                // Typed receivers like `self: &Arc<Self>` are parsed as `FnArg::Typed`.
+                Some(FnArg::Typed(arg)) if is_self_pat(&arg.pat) => 1,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Returns an error if any arguments were given that can't be applied to trait impls.
+    pub(crate) fn check_impl_args(&self) -> syn::Result<()> {
+        match self.given.iter().find(|(_, trait_only)| *trait_only) {
+            Some((name, _)) => Err(syn::Error::new_spanned(
+                name,
+                format!(
+                    "hazmat::suit argument `{}` can only be applied to traits",
+                    name
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an error if the argument `name` was given along with any of `others`.
+    pub(crate) fn check_conflicts(&self, name: &str, others: &[&str]) -> syn::Result<()> {
+        if self.given.iter().any(|(given, _)| given == name) {
+            if let Some((other, _)) = self
+                .given
+                .iter()
+                .find(|(given, _)| others.iter().any(|o| given == o))
+            {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    format!(
+                        "hazmat::suit argument `{}` can't be combined with `{}`",
+                        other, name
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Parse for SuitArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = SuitArgs::default();
+
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            // Arguments that change the signatures of methods must also be usable on trait
+            // impls, so that the impls match the trait.
+            let trait_only = match name.to_string().as_str() {
+                "thread_local" => {
+                    set_once(&mut args.thread_local, &name, name.clone())?;
+                    if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        let mode: LitStr = input.parse()?;
+                        if mode.value() != "negative_impls" {
+                            return Err(syn::Error::new_spanned(
+                                mode,
+                                "unknown hazmat::suit thread_local mode (expected \
+                                 `negative_impls`)",
+                            ));
+                        }
+                        args.thread_local_negative_impls = Some(mode);
+                    }
+                    true
+                }
+                "pinned" => {
+                    set_once(&mut args.pinned, &name, name.clone())?;
+                    true
+                }
+                "emit_tests" => {
+                    set_once(&mut args.emit_tests, &name, name.clone())?;
+                    true
+                }
+                "verify_arity" => {
+                    set_once(&mut args.verify_arity, &name, name.clone())?;
+                    true
+                }
+                "require_explicit_cap" => {
+                    set_once(&mut args.require_explicit_cap, &name, name.clone())?;
+                    true
+                }
+                "unsafe_ctor" => {
+                    set_once(&mut args.unsafe_ctor, &name, name.clone())?;
+                    true
+                }
+                "doc_ctor" => {
+                    set_once(&mut args.doc_ctor, &name, name.clone())?;
+                    true
+                }
+                "log_usage" => {
+                    if !cfg!(feature = "tracing") {
+                        return Err(syn::Error::new_spanned(
+                            &name,
+                            "hazmat::suit argument `log_usage` requires the `tracing` feature flag",
+                        ));
+                    }
+                    set_once(&mut args.log_usage, &name, name.clone())?;
+                    true
+                }
+                "safe_ext" => {
+                    input.parse::<Token![=]>()?;
+                    let ext: LitStr = input.parse()?;
+                    set_once(&mut args.safe_ext, &name, ext.parse()?)?;
+                    true
+                }
+                "mint_via" => {
+                    input.parse::<Token![=]>()?;
+                    set_once(&mut args.mint_via, &name, input.parse()?)?;
+                    true
+                }
+                "from_seed" => {
+                    input.parse::<Token![=]>()?;
+                    set_once(&mut args.from_seed, &name, input.parse()?)?;
+                    true
+                }
+                "audit_callback" => {
+                    input.parse::<Token![=]>()?;
+                    set_once(&mut args.audit_callback, &name, input.parse()?)?;
+                    true
+                }
+                "preset" => {
+                    input.parse::<Token![=]>()?;
+                    let preset: LitStr = input.parse()?;
+                    let preset = match preset.value().as_str() {
+                        "standard" => Preset::Standard,
+                        "minimal" => Preset::Minimal,
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                preset,
+                                format!(
+                                    "unknown hazmat::suit preset `{}` (expected `standard` or `minimal`)",
+                                    other
+                                ),
+                            ))
+                        }
+                    };
+                    set_once(&mut args.preset, &name, preset)?;
+                    true
+                }
+                "derive" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let traits = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                    args.derives.extend(traits);
+                    true
+                }
+                "cap_impl" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let traits = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                    args.cap_impls.extend(traits);
+                    true
+                }
+                "sealed" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                    if types.is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            &name,
+                            "hazmat::suit(sealed) requires at least one type",
+                        ));
+                    }
+                    args.sealed.extend(types);
+                    true
+                }
+                "by_ref" => {
+                    set_once(&mut args.by_ref, &name, name.clone())?;
+                    false
+                }
+                "ambient" => {
+                    set_once(&mut args.ambient, &name, name.clone())?;
+                    false
+                }
+                "generic_cap" => {
+                    set_once(&mut args.generic_cap, &name, name.clone())?;
+                    false
+                }
+                "position" => {
+                    input.parse::<Token![=]>()?;
+                    let position: LitStr = input.parse()?;
+                    let position = match position.value().as_str() {
+                        "last" => Position::Last,
+                        "after_receiver" => Position::AfterReceiver,
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                position,
+                                format!(
+                                    "unknown hazmat::suit position `{}` (expected `last` or `after_receiver`)",
+                                    other
+                                ),
+                            ))
+                        }
+                    };
+                    set_once(&mut args.position, &name, position)?;
+                    false
+                }
+                "cap_default_impls" => {
+                    input.parse::<Token![=]>()?;
+                    let impls: LitStr = input.parse()?;
+                    if impls.value() != "none" {
+                        return Err(syn::Error::new_spanned(
+                            impls,
+                            "unknown hazmat::suit cap_default_impls (expected `none`)",
+                        ));
+                    }
+                    set_once(&mut args.cap_default_impls_none, &name, impls)?;
+                    true
+                }
+                "display" => {
+                    input.parse::<Token![=]>()?;
+                    set_once(&mut args.display, &name, input.parse()?)?;
+                    true
+                }
+                "cap_size" => {
+                    input.parse::<Token![=]>()?;
+                    let size: LitStr = input.parse()?;
+                    if size.value() != "zero" {
+                        return Err(syn::Error::new_spanned(
+                            size,
+                            "unknown hazmat::suit cap_size (expected `zero`)",
+                        ));
+                    }
+                    set_once(&mut args.cap_size_zero, &name, size)?;
+                    true
+                }
+                "no_std_core" => {
+                    input.parse::<Token![=]>()?;
+                    let core: LitStr = input.parse()?;
+                    set_once(&mut args.no_std_core, &name, core.parse()?)?;
+                    true
+                }
+                "cap_ty" => {
+                    input.parse::<Token![=]>()?;
+                    set_once(&mut args.cap_ty, &name, input.parse()?)?;
+                    false
+                }
+                "cap_in_mod" => {
+                    input.parse::<Token![=]>()?;
+                    let module: LitStr = input.parse()?;
+                    set_once(&mut args.cap_in_mod, &name, module.parse()?)?;
+                    true
+                }
+                "cap_module" => {
+                    input.parse::<Token![=]>()?;
+                    let prefix: LitStr = input.parse()?;
+                    set_once(&mut args.cap_module, &name, prefix.parse()?)?;
+                    false
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        format!("unknown hazmat::suit argument `{}`", name),
+                    ))
+                }
+            };
+            args.given.push((name, trait_only));
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(args)
+    }
+}
+
+/// Returns whether the given pattern binds `self`.
+fn is_self_pat(pat: &Pat) -> bool {
+    matches!(pat, Pat::Ident(pat) if pat.ident == "self")
+}
+
+/// Sets `field` to `value`, returning an error if it was already set.
+fn set_once<T>(field: &mut Option<T>, name: &Ident, value: T) -> syn::Result<()> {
+    if field.is_some() {
+        return Err(syn::Error::new_spanned(
+            name,
+            format!("duplicate hazmat::suit argument `{}`", name),
+        ));
+    }
+    *field = Some(value);
+    Ok(())
+}
@@ -1,6 +1,12 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{parse_macro_input, parse_quote};
+use syn::{
+    parse_macro_input, parse_quote,
+    visit::{self, Visit},
+};
+
+mod args;
+use args::SuitArgs;
 
 /// Protects users from low-level traits by donning a [hazmat suit].
 ///
@@ -128,82 +134,2585 @@ use syn::{parse_macro_input, parse_quote};
 ///     }
 /// }
 /// ```
-#[proc_macro_attribute]
-pub fn suit(
-    _attr: proc_macro::TokenStream,
-    item: proc_macro::TokenStream,
-) -> proc_macro::TokenStream {
-    // Parse the `TokenStream` into a syntax tree.
-    let item = parse_macro_input!(item as syn::Item);
-
-    let augmented = match item {
-        // When applied to a trait, restrict the trait's methods with a capability.
-        syn::Item::Trait(t) => augment_trait(t),
-
-        // When applied to a trait impl, append the expected capability argument.
-        syn::Item::Impl(t) if t.trait_.is_some() => augment_trait_impl(t),
-
-        // When applied to any other kind of item, generate a compiler error.
-        _ => syn::Error::new_spanned(
-            item,
-            "hazmat::suit should be applied to traits or trait impls",
-        )
-        .into_compile_error(),
-    };
-    augmented.into()
-}
-
-fn augment_trait(mut t: syn::ItemTrait) -> TokenStream {
-    // Create a name for the capability corresponding to this trait.
-    let cap_name = syn::Ident::new(&format!("{}Cap", t.ident), Span::call_site());
-
-    // Modify the trait to add the capability to each method as an argument.
-    for item in &mut t.items {
-        if let syn::TraitItem::Method(method) = item {
-            let cap_arg = syn::PatType {
-                attrs: vec![],
-                pat: parse_quote!(cap),
-                colon_token: parse_quote!(:),
-                ty: parse_quote!(#cap_name),
-            };
-            method.sig.inputs.push(cap_arg.into());
-        }
-    }
-
-    quote! {
-        #[non_exhaustive]
-        pub struct #cap_name;
-
-        #t
-    }
-}
-
-fn augment_trait_impl(mut t: syn::ItemImpl) -> TokenStream {
-    // Create a name for the capability corresponding to this trait.
-    let trait_path = &t.trait_.as_ref().unwrap().1;
-    let trait_name = &trait_path.segments.last().unwrap().ident;
-    let cap_name = syn::Ident::new(&format!("{}Cap", trait_name), Span::call_site());
-    let cap_path = {
-        let mut p = trait_path.clone();
-        p.segments.pop();
-        p.segments.push(cap_name.into());
-        p
-    };
-
-    // Modify the trait implementation to add the capability to each method.
-    for item in &mut t.items {
-        if let syn::ImplItem::Method(method) = item {
-            let cap_arg = syn::PatType {
-                attrs: vec![],
-                pat: parse_quote!(cap),
-                colon_token: parse_quote!(:),
-                ty: parse_quote!(#cap_path),
-            };
-            method.sig.inputs.push(cap_arg.into());
-        }
-    }
-
-    quote! {
-        #t
+///
+/// Within the crate that defines the trait, the capability can be constructed with
+/// `AddOnceCap::new()`, or obtained from the constant `AddOnceCap::INSTANCE`. Both are
+/// `pub(crate)`, so downstream crates can't use them. (`INSTANCE` isn't generated with
+/// `audit_callback`, `mint_via`, `from_seed`, `unsafe_ctor`, or
+/// `cap_default_impls = "none"`.)
+///
+/// The capability argument is named `cap`, so default method bodies can forward it to
+/// other methods of the trait (including receiverless associated functions):
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit]
+/// pub trait Build {
+///     fn build(seed: u32) -> Self;
+///
+///     fn build_default() -> Self
+///     where
+///         Self: Sized,
+///     {
+///         Self::build(0, cap)
+///     }
+/// }
+/// ```
+///
+/// A default body can also return a closure that captures `cap`. If the closure can be
+/// called more than once (`Fn` or `FnMut`), each call needs its own capability, so the
+/// capability must be `Copy` (for example via `preset = "standard"`). The same applies to
+/// async closures returned as `impl AsyncFn(..)`, which are stable since Rust 1.85:
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(preset = "standard")]
+/// pub trait Sign {
+///     fn sign(&self, msg: &[u8]) -> u32;
+///
+///     fn signer(&self) -> impl Fn(&[u8]) -> u32 + '_ {
+///         move |msg| self.sign(msg, cap)
+///     }
+/// }
+/// ```
+///
+/// Capabilities that carry data (such as those used with `#[hazmat::marker]`) keep it in
+/// private fields, so they can't be constructed or destructured to access that data
+/// outside of the module that defines the trait. Like any other struct, a capability can
+/// still be matched with the pattern `AddOnceCap { .. }`, but that pattern binds nothing
+/// and so grants no additional authority.
+///
+/// Generated capabilities have no lifetime parameters, so they are `'static` and can be
+/// stored in type-erased containers (for example as a `Box<dyn Any>`). Unless the
+/// `thread_local` argument is given, they are also `Send` and `Sync`; the generated code
+/// asserts this at compile time.
+///
+/// Attribute macros are expanded from the top down, and `#[hazmat::suit]` only sees the
+/// methods that exist when it is expanded. When combining it with an attribute macro that
+/// generates trait methods, place `#[hazmat::suit]` below that attribute so that the
+/// generated methods also require the capability.
+///
+/// # Arguments
+///
+/// The following arguments can be passed to `#[hazmat::suit(..)]` when it is applied to a
+/// trait:
+///
+/// - `thread_local`: makes the capability `!Send` and `!Sync`, so that it can't leave the
+///   thread on which it was constructed. By default this is done by giving the capability
+///   a `PhantomData<*const ()>` field. With `thread_local = "negative_impls"`, the
+///   capability is instead given explicit `impl !Send` and `impl !Sync` blocks; this
+///   requires the crate defining the trait to enable the nightly
+///   `#![feature(negative_impls)]`.
+///
+///   Note that a future that holds a thread-local capability (such as the future returned
+///   by an `async fn` that takes one) is also `!Send`, regardless of whether its output
+///   (for example a `Box<dyn Error + Send + Sync>`) is `Send`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(thread_local)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn add_locally<T: AddOnce>(a: T, b: &T) -> T {
+///     a.add_once(b, AddOnceCap::new())
+/// }
+/// ```
+///
+/// - `audit_callback = path::to::callback`: makes `AddOnceCap::new()` call
+///   `path::to::callback(cap_name, location)` every time a capability is constructed,
+///   where `cap_name` is the capability's name (`"AddOnceCap"`) and `location` is the
+///   `&'static core::panic::Location<'static>` of the caller. This lets the trait author
+///   audit every authorization at its source. The constructor is no longer `const`, and
+///   `AddOnceCap::INSTANCE` isn't generated, so that every capability passes through the
+///   callback.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// use core::panic::Location;
+///
+/// fn on_cap_minted(cap: &'static str, location: &'static Location<'static>) {
+///     println!("{} minted at {}", cap, location);
+/// }
+///
+/// #[hazmat::suit(audit_callback = on_cap_minted)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn add_audited<T: AddOnce>(a: T, b: &T) -> T {
+///     a.add_once(b, AddOnceCap::new())
+/// }
+/// ```
+///
+/// - `pinned`: makes the capability `!Unpin` by giving it a `PhantomPinned` field, for
+///   primitives whose state must not move while the capability is held. This doesn't
+///   affect construction with `AddOnceCap::new()`.
+///
+/// - `mint_via = path::to::Guard`: changes the crate-internal constructor to
+///   `AddOnceCap::new(_: &path::to::Guard)`, so that every capability must be minted using
+///   a reference to a `Guard`. By controlling where `Guard` is constructed, the trait author
+///   can centralize where hazardous authority originates within their crate.
+///   `AddOnceCap::INSTANCE` isn't generated, because it wouldn't need a guard.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// pub struct ProvenanceGuard(());
+///
+/// #[hazmat::suit(mint_via = ProvenanceGuard)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn add_with_provenance<T: AddOnce>(a: T, b: &T, guard: &ProvenanceGuard) -> T {
+///     a.add_once(b, AddOnceCap::new(guard))
+/// }
+/// ```
+///
+/// - `from_seed = path::to::Seed`: like `mint_via = path::to::Seed`, and additionally
+///   implements `From<&path::to::Seed>` for the capability. This suits crates that
+///   establish authority once at initialization: whichever code holds the seed can mint
+///   capabilities with `.into()`. The seed type should be crate-private (or have private
+///   fields), so that downstream crates can't obtain one. As with `mint_via`,
+///   `AddOnceCap::INSTANCE` isn't generated. This can't be combined with `mint_via`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// pub(crate) struct AuthoritySeed(());
+///
+/// #[hazmat::suit(from_seed = AuthoritySeed)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn add_with_authority<T: AddOnce>(a: T, b: &T, seed: &AuthoritySeed) -> T {
+///     a.add_once(b, seed.into())
+/// }
+/// ```
+///
+/// - `emit_tests`: generates a `#[cfg(test)]` module named `__hazmat_tests_{Cap}` (e.g.
+///   `__hazmat_tests_AddOnceCap`) alongside the capability, containing the tests
+///   `cap_is_zero_sized` and `cap_is_constructible`. These give the trait author
+///   regression coverage of the capability's basic properties. (`cap_is_constructible` is
+///   omitted for capabilities minted via a guard or seed.) If the trait is `pub`, the
+///   capability's documentation also gains a `compile_fail` doctest showing that it can't
+///   be constructed by downstream crates, which is run by `cargo test --doc` when the trait
+///   is defined in a library. The doctest names the capability by its module path, so for
+///   a trait in a private module it fails to compile for that reason instead, and checks
+///   nothing.
+/// - `verify_arity`: emits a compile-time check that every method of the emitted trait
+///   gained exactly one argument (or none, with `ambient` or `#[hazmat::skip]`), by
+///   coercing it to a function pointer with the expected number of arguments. Methods
+///   with type or const generic parameters (including `impl Trait` arguments) aren't
+///   checked. This is a self-check of the macro, for maintainers and cautious users; it
+///   has no effect on the generated trait.
+/// - `require_explicit_cap`: forbids applying `#[hazmat::suit]` to impls of the trait, so
+///   that each implementation must acknowledge the hazard by writing the capability
+///   parameter itself (as `cap: AddOnceCap`). Using the impl helper anyway fails to
+///   compile with an error saying so. A note is added to the trait's documentation. This
+///   can't be combined with `ambient`, `cap_ty`, or `generic_cap`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(require_explicit_cap)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// struct MyNum(u32);
+///
+/// impl AddOnce for MyNum {
+///     fn add_once(self, other: &Self, _cap: AddOnceCap) -> Self {
+///         Self(self.0 + other.0)
+///     }
+/// }
+/// ```
+///
+/// - `cap_default_impls = "none"`: generates only the capability struct, its constructor,
+///   and the hidden items that other parts of the macro rely on. The capability gets no
+///   derives, trait impls, generated documentation, or `INSTANCE` constant, including any
+///   that future versions of this crate might add by default. This can't be combined
+///   with `preset`, `derive`, `cap_impl`, `display`, `emit_tests`, or `from_seed`.
+/// - `doc_ctor`: makes the capability's `new()` constructor `pub` under `#[cfg(doc)]`, so
+///   that rustdoc renders it and `#[cfg(doc)]`-only items can construct the capability
+///   for illustration. In every other build (including doctests, which compile the crate
+///   without `cfg(doc)`) it stays `pub(crate)`. This can't be combined with `ambient` or
+///   `cap_ty`.
+/// - `unsafe_ctor`: replaces the capability's `new()` constructor with a `pub(crate)
+///   unsafe fn new_unchecked()`, so that every place in the defining crate that mints the
+///   capability has to be written as `unsafe { AddOnceCap::new_unchecked() }`. This
+///   doesn't change what downstream crates can do; it only makes authority creation stand
+///   out during review. `AddOnceCap::INSTANCE` isn't generated, because it would mint
+///   the capability without `unsafe`. Crates that use `#![forbid(unsafe_code)]` should
+///   leave it off, as the default constructor emits no `unsafe` code. This can't be
+///   combined with `safe_ext`, `from_seed`, `ambient`, or `cap_ty`.
+/// - `cap_impl(path::to::Trait, ..)`: generates an empty `impl path::to::Trait for
+///   AddOnceCap {}` for each listed trait. This can be used to implement marker traits
+///   (that have no required items) for the capability. The argument can be repeated.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// pub trait Authorization {}
+///
+/// #[hazmat::suit(cap_impl(Authorization))]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn authorize<A: Authorization>(cap: A) -> A {
+///     cap
+/// }
+///
+/// fn add_authorized<T: AddOnce>(a: T, b: &T) -> T {
+///     a.add_once(b, authorize(AddOnceCap::new()))
+/// }
+/// ```
+///
+/// - `sealed(Type, ..)`: seals the trait, so that only the listed types can implement it.
+///   The trait gains a supertrait defined in a private module next to it, which is
+///   implemented for each listed type; other crates can't name that supertrait, so they
+///   can't implement it or the trait. The types are named relative to the trait's
+///   module, and generic types must be listed with concrete arguments. The argument can
+///   be repeated.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// pub struct MyNum(u32);
+///
+/// #[hazmat::suit(sealed(MyNum))]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// #[hazmat::suit]
+/// impl AddOnce for MyNum {
+///     fn add_once(self, other: &Self) -> Self {
+///         Self(self.0 + other.0)
+///     }
+/// }
+/// ```
+///
+/// - `display = "label"`: implements `Display` for the capability, writing the given
+///   label (honoring the formatter's width and alignment). This is intended for
+///   human-readable audit logs. Only the label is written, even for capabilities that
+///   carry data (such as a `pinned` capability's marker).
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(display = "raw-sign-authorization")]
+/// pub trait SignRaw {
+///     fn sign_raw(&self, msg: &[u8]) -> u32;
+/// }
+///
+/// assert_eq!(SignRawCap::new().to_string(), "raw-sign-authorization");
+/// ```
+///
+/// - `derive(Trait, ..)`: adds `#[derive(Trait, ..)]` to the capability. The argument can
+///   be repeated. Capabilities are zero-sized, so `PartialEq` always returns `true`; for
+///   marked capabilities, `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq`, `PartialOrd`, `Ord`,
+///   and `Hash` are implemented directly so that they don't require the marker type to
+///   implement them. Any other derived trait must also be implemented by every marker
+///   type. Structs holding capabilities can then derive these traits too.
+/// - `preset = "standard"`: derives `Debug`, `Clone`, and `Copy` for the capability. These
+///   don't grant any additional authority, because a capability can only be cloned or
+///   copied by code that already holds one. `Default` is deliberately not included, as it
+///   would allow anyone to construct the capability; use `AddOnceCap::new()` instead.
+///   `preset = "minimal"` derives nothing, which is the same as giving no preset. Presets
+///   can be combined with `derive(..)`. For marked capabilities, these traits are
+///   implemented directly, so that they don't require the marker type to implement them.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(preset = "standard", derive(PartialEq))]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn add_twice<T: AddOnce>(a: T, b: &T) -> T {
+///     let cap = AddOnceCap::new();
+///     a.add_once(b, cap).add_once(b, cap)
+/// }
+/// ```
+///
+/// - `cap_size = "zero"`: asserts at compile time that the capability is zero-sized, so
+///   that a change which adds data to the capability (or an existing type passed via
+///   `cap_ty`) fails to compile. Unlike `emit_tests`, this is checked in every build.
+///
+/// - `no_std_core = "path::to::core"`: uses the given path instead of `::core` in the
+///   generated code, for crates that access `core` through a shim. The shim must expose
+///   the same module layout as `core` for the items that the generated code uses (such as
+///   `marker::PhantomData`, or the `concat!` and `module_path!` macros with `emit_tests`).
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// mod core_shim {
+///     pub use core::{marker, mem};
+/// }
+///
+/// #[hazmat::suit(no_std_core = "crate::core_shim")]
+/// pub trait AddOnce {
+///     #[hazmat::marker(u8)]
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// - `log_usage`: enters a `tracing::debug_span!("{Trait}::{method}")` span for the
+///   duration of each default method body. The bodies of `async fn`s are instead
+///   instrumented with the span (using `tracing::Instrument`), so that the span isn't held
+///   across `.await` points and their futures stay `Send`. The crate defining the trait
+///   must depend on `tracing`, and this crate's `tracing` feature flag must be enabled.
+///   Only default method bodies can be logged; methods implemented by downstream crates
+///   are not.
+///
+/// - `safe_ext = "AddOnceExt"`: generates an extension trait `AddOnceExt: AddOnce`, with
+///   a blanket implementation for all implementors of the trait. For each method `foo` of
+///   the trait, the extension trait has a method `foo_safe` with the original signature
+///   (without the capability argument), which calls `foo` with a capability constructed
+///   internally. This is a shorthand for the high-level trait pattern shown above, and
+///   should only be used for traits whose methods are not inherently hazardous to call.
+///   The blanket implementation is marked `#[diagnostic::do_not_recommend]`, so unmet
+///   `AddOnceExt` bounds are reported in terms of `AddOnceExt` rather than the blanket
+///   implementation.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(safe_ext = "AddOnceExt")]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// struct MyNum(u32);
+///
+/// #[hazmat::suit]
+/// impl AddOnce for MyNum {
+///     fn add_once(self, other: &Self) -> Self {
+///         Self(self.0 + other.0)
+///     }
+/// }
+///
+/// assert_eq!(MyNum(1).add_once_safe(&MyNum(2)).0, 3);
+/// ```
+///
+/// The following arguments change the signatures of the trait's methods, and so must be
+/// passed both to the trait and to every `#[hazmat::suit]` impl of it:
+///
+/// - `by_ref`: methods take the capability by reference (`cap: &AddOnceCap`) instead of
+///   by value. This is rejected for methods whose return type contains elided lifetimes
+///   and that don't have a `&self` or `&mut self` receiver, because the additional
+///   reference would make the elided lifetimes ambiguous. Note also that a
+///   return-position `impl Trait` captures the lifetime of the capability reference, so the
+///   returned value can't outlive the capability.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(by_ref)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// struct MyNum(u32);
+///
+/// #[hazmat::suit(by_ref)]
+/// impl AddOnce for MyNum {
+///     fn add_once(self, other: &Self) -> Self {
+///         Self(self.0 + other.0)
+///     }
+/// }
+///
+/// assert_eq!(MyNum(1).add_once(&MyNum(2), &AddOnceCap::new()).0, 3);
+/// ```
+///
+/// - `position = "after_receiver"`: inserts the capability argument immediately after the
+///   method's receiver (or as the first argument of methods without one), instead of after
+///   all other arguments (`position = "last"`, the default). Typed receivers such as
+///   `self: &Arc<Self>` and `self: Pin<Arc<Self>>` are recognised as receivers.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(position = "after_receiver")]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn add_twice<T: AddOnce>(a: T, b: &T) -> T {
+///     a.add_once(AddOnceCap::new(), b)
+///         .add_once(AddOnceCap::new(), b)
+/// }
+/// ```
+///
+/// - `ambient`: instead of adding a capability argument, checks at runtime that the
+///   capability is present on the current thread. The generated `AddOnceCap` has a
+///   crate-internal `AddOnceCap::with_capability(|| ..)` function that makes the
+///   capability present for the duration of the closure (scopes can be nested), and a
+///   public `AddOnceCap::is_present()`. Every default method body, and every method
+///   body of a suited impl, panics if the capability isn't present. For an `async fn`,
+///   this is checked when the future is first polled rather than when it is created, so
+///   the future must be polled within `with_capability`. This trades static enforcement
+///   for dynamic enforcement, for traits whose method signatures can't change. Methods
+///   of impls that aren't suited are not checked. The generated code requires `std`,
+///   and this can't be combined with any other argument except `log_usage`,
+///   `no_std_core`, `verify_arity`, and `sealed`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(ambient)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// struct MyNum(u32);
+///
+/// #[hazmat::suit(ambient)]
+/// impl AddOnce for MyNum {
+///     fn add_once(self, other: &Self) -> Self {
+///         Self(self.0 + other.0)
+///     }
+/// }
+///
+/// let sum = AddOnceCap::with_capability(|| MyNum(1).add_once(&MyNum(2)));
+/// assert_eq!(sum.0, 3);
+///
+/// // Outside of the scope, the method panics.
+/// assert!(std::panic::catch_unwind(|| MyNum(1).add_once(&MyNum(2))).is_err());
+/// ```
+///
+/// - `generic_cap`: gives the capability the same generic parameters as the trait (without
+///   their bounds), so that `trait Block<const N: usize>` has a capability `BlockCap<N>`,
+///   and `impl Block<16> for X` takes a `BlockCap<16>`. Impls can rely on the trait's
+///   default generic arguments, unless one of the defaults mentions `Self`, in which case
+///   they must spell out all of the trait's generic arguments. This can't be combined
+///   with `#[hazmat::marker]`, `emit_tests`, `cap_size`, or `cap_ty`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(generic_cap)]
+/// pub trait Block<const N: usize> {
+///     fn xor(&self, other: &Self) -> Self;
+/// }
+///
+/// struct Bytes<const N: usize>([u8; N]);
+///
+/// #[hazmat::suit(generic_cap)]
+/// impl Block<16> for Bytes<16> {
+///     fn xor(&self, other: &Self) -> Self {
+///         Bytes(core::array::from_fn(|i| self.0[i] ^ other.0[i]))
+///     }
+/// }
+///
+/// let block = Bytes([1; 16]).xor(&Bytes([3; 16]), BlockCap::<16>::new());
+/// assert_eq!(block.0, [2; 16]);
+/// ```
+///
+/// - `cap_module = "crate_name"`: places the generated capability in a hidden module named
+///   `__hazmat_<crate_name>_<Trait>` (such as `__hazmat_my_crate_Raw::RawCap`), instead
+///   of next to the trait. Capabilities of same-named traits from different crates then
+///   stay unambiguous when a downstream crate glob-imports both crates. The value should be
+///   the defining crate's name as written in paths, and must be given on impls too. This
+///   can't be combined with `ambient` or `cap_ty`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[hazmat::suit(cap_module = "my_crate")]
+/// pub trait Raw {
+///     fn raw(&self) -> u8;
+/// }
+///
+/// struct Byte(u8);
+///
+/// #[hazmat::suit(cap_module = "my_crate")]
+/// impl Raw for Byte {
+///     fn raw(&self) -> u8 {
+///         self.0
+///     }
+/// }
+///
+/// assert_eq!(Byte(7).raw(__hazmat_my_crate_Raw::RawCap::new()), 7);
+/// ```
+///
+/// - `cap_in_mod = "path::to::module"`: requires the capability to be re-exported from
+///   `crate::path::to::module`, so that a crate with many suited traits can collect all of
+///   their capabilities in one place. A proc macro can't add items to another module, so
+///   the author declares the module once and adds a `pub use` for each capability; the
+///   macro then fails to compile if the re-export is missing or names a different type.
+///   This can't be combined with `ambient`, `cap_ty`, or `generic_cap`.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// pub mod capabilities {
+///     pub use crate::hashing::HashCap;
+///     pub use crate::signing::SignCap;
+/// }
+///
+/// pub mod hashing {
+/// #   use crate::hazmat;
+///     #[hazmat::suit(cap_in_mod = "capabilities")]
+///     pub trait Hash {
+///         fn hash(&self) -> u32;
+///     }
+/// }
+///
+/// pub mod signing {
+/// #   use crate::hazmat;
+///     #[hazmat::suit(cap_in_mod = "capabilities")]
+///     pub trait Sign {
+///         fn sign(&self, msg: u32) -> u32;
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// - `cap_ty = path::to::Cap`: uses an existing type as the capability, instead of
+///   generating one. This enables several traits to share a capability. The trait author
+///   is responsible for ensuring that the type can't be constructed by downstream crates;
+///   the macro can only check that the type exists. This can't be combined with the
+///   arguments that configure the generated capability. The type may take generic
+///   arguments, including `Self`: with `cap_ty = SharedCap<Self>`, an impl for `Foo` takes
+///   a `SharedCap<Foo>`, tying the shared capability to each implementor. (`Self` isn't
+///   `Sized` within the trait, so `SharedCap` must accept `?Sized` arguments.)
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// #[non_exhaustive]
+/// pub struct ArithmeticCap;
+///
+/// #[hazmat::suit(cap_ty = ArithmeticCap)]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+/// }
+///
+/// #[hazmat::suit(cap_ty = ArithmeticCap)]
+/// pub trait MulOnce {
+///     fn mul_once(self, other: &Self) -> Self;
+/// }
+///
+/// fn fma<T: AddOnce + MulOnce>(a: T, b: &T, c: &T) -> T {
+///     a.mul_once(b, ArithmeticCap).add_once(c, ArithmeticCap)
+/// }
+/// ```
+///
+///   Combined with `by_ref`, this enables stateful capabilities: the trait author passes a
+///   reference to a type whose construction they control (such as a session handle), so
+///   that holding the reference proves authorization and gives implementations access to
+///   its state.
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::suit;
+/// # }
+/// pub struct SessionHandle {
+///     id: u32,
+/// }
+///
+/// #[hazmat::suit(cap_ty = SessionHandle, by_ref)]
+/// pub trait Transmit {
+///     fn transmit(&self, msg: u32) -> u32;
+/// }
+///
+/// fn transmit<T: Transmit>(t: &T, msg: u32) -> u32 {
+///     let session = SessionHandle { id: 7 };
+///     t.transmit(msg, &session)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn suit(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    // Parse the `TokenStream`s into syntax trees.
+    let args = parse_macro_input!(attr as SuitArgs);
+    let item = parse_macro_input!(item as syn::Item);
+
+    let augmented = match item {
+        // When applied to a trait, restrict the trait's methods with a capability.
+        syn::Item::Trait(t) => augment_trait(t, &args),
+
+        // When applied to a trait impl, append the expected capability argument.
+        syn::Item::Impl(t) if t.trait_.is_some() => args
+            .check_impl_args()
+            .and_then(|()| augment_trait_impl(t, &args)),
+
+        // When applied to any other kind of item, generate a compiler error.
+        _ => Err(syn::Error::new_spanned(
+            item,
+            "hazmat::suit should be applied to traits or trait impls",
+        )),
+    };
+    augmented
+        .map(check_output)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// In debug builds of the macro, checks that the generated code parses, so that bugs in
+/// the macro are reported as such instead of as confusing errors in downstream code.
+fn check_output(tokens: TokenStream) -> TokenStream {
+    if cfg!(debug_assertions) {
+        if let Err(e) = syn::parse2::<syn::File>(tokens.clone()) {
+            return syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "hazmat::suit generated code that doesn't parse ({}); this is a bug in \
+                     hazmat. The generated code was: {}",
+                    e, tokens
+                ),
+            )
+            .into_compile_error();
+        }
+    }
+    tokens
+}
+
+/// Marks a method of a `#[hazmat::suit]` trait as requiring a capability that carries the
+/// given marker type.
+///
+/// By default, every method of a suited trait takes the same capability type. Adding
+/// `#[hazmat::marker(SomeMarker)]` to a method instead makes it take `{Trait}Cap<SomeMarker>`,
+/// which is a distinct type from the capabilities required by differently-marked (or
+/// unmarked) methods of the same trait. This enables the trait author to control more
+/// precisely which operations each constructed capability authorizes.
+///
+/// When any method of a trait is marked, the generated capability becomes generic over
+/// its marker (defaulting to `()` for unmarked methods), and is constructed with
+/// `{Trait}Cap::new::<SomeMarker>()`. The attribute must be applied to the method in both
+/// the trait definition and any `#[hazmat::suit]` trait impls.
+///
+/// # Examples
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::{marker, suit};
+/// # }
+/// pub struct SignOp;
+/// pub struct VerifyOp;
+///
+/// #[hazmat::suit]
+/// pub trait Signer {
+///     #[hazmat::marker(SignOp)]
+///     fn sign(&self, msg: &[u8]) -> [u8; 4];
+///
+///     #[hazmat::marker(VerifyOp)]
+///     fn verify(&self, msg: &[u8], sig: &[u8; 4]) -> bool;
+/// }
+///
+/// fn sign_and_verify<S: Signer>(signer: &S, msg: &[u8]) -> bool {
+///     let sig = signer.sign(msg, SignerCap::new::<SignOp>());
+///     signer.verify(msg, &sig, SignerCap::new::<VerifyOp>())
+/// }
+/// ```
+///
+/// A capability minted for one marker can't be used for a differently-marked method:
+///
+/// ```compile_fail,E0308
+/// # mod hazmat {
+/// #    pub use hazmat_macros::{marker, suit};
+/// # }
+/// pub struct SignOp;
+/// pub struct VerifyOp;
+///
+/// #[hazmat::suit]
+/// pub trait Signer {
+///     #[hazmat::marker(SignOp)]
+///     fn sign(&self, msg: &[u8]) -> [u8; 4];
+/// }
+///
+/// fn sign<S: Signer>(signer: &S, msg: &[u8]) -> [u8; 4] {
+///     signer.sign(msg, SignerCap::new::<VerifyOp>())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn marker(
+    _attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    // `#[hazmat::suit]` removes this attribute from the methods it augments, so if this
+    // macro is ever expanded, it has been misused.
+    let item = proc_macro2::TokenStream::from(item);
+    syn::Error::new_spanned(
+        item,
+        "hazmat::marker should be applied to methods of #[hazmat::suit] traits or trait impls",
+    )
+    .into_compile_error()
+    .into()
+}
+
+/// Excludes a method of a `#[hazmat::suit]` trait from requiring the capability.
+///
+/// Default methods that don't use the trait's hazardous primitives (such as helpers built
+/// on other traits) don't need a capability. Adding `#[hazmat::skip]` to such a method
+/// leaves its signature unchanged, so `cap` is not available in its default body. The
+/// attribute must be applied to the method in both the trait definition and any
+/// `#[hazmat::suit]` trait impls that override it, and can't be combined with
+/// `#[hazmat::marker]`. Skipped methods are omitted from `safe_ext` extension traits.
+///
+/// # Examples
+///
+/// ```
+/// # mod hazmat {
+/// #    pub use hazmat_macros::{skip, suit};
+/// # }
+/// #[hazmat::suit]
+/// pub trait AddOnce {
+///     fn add_once(self, other: &Self) -> Self;
+///
+///     #[hazmat::skip]
+///     fn dup(&self) -> Self
+///     where
+///         Self: Clone,
+///     {
+///         self.clone()
+///     }
+/// }
+///
+/// fn dup_twice<T: AddOnce + Clone>(t: &T) -> (T, T) {
+///     (t.dup(), t.dup())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn skip(
+    _attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    // `#[hazmat::suit]` removes this attribute from the methods it augments, so if this
+    // macro is ever expanded, it has been misused.
+    let item = proc_macro2::TokenStream::from(item);
+    syn::Error::new_spanned(
+        item,
+        "hazmat::skip should be applied to methods of #[hazmat::suit] traits or trait impls",
+    )
+    .into_compile_error()
+    .into()
+}
+
+/// Returns whether `attr` is the `#[hazmat::{name}]` attribute.
+fn is_hazmat_attr(attr: &syn::Attribute, name: &str) -> bool {
+    let segments = &attr.path.segments;
+    segments.len() == 2
+        && (segments[0].ident == "hazmat" || segments[0].ident == "hazmat_macros")
+        && segments[1].ident == name
+}
+
+/// Removes any `#[hazmat::marker(..)]` attribute from `attrs`, and returns its marker.
+fn take_marker(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Option<syn::Type>> {
+    let mut marker = None;
+    let mut res = Ok(());
+    attrs.retain(|attr| {
+        if !is_hazmat_attr(attr, "marker") {
+            return true;
+        }
+        if marker.is_some() {
+            res = Err(syn::Error::new_spanned(
+                attr,
+                "duplicate hazmat::marker attribute",
+            ));
+        } else {
+            match attr.parse_args() {
+                Ok(ty) => marker = Some(ty),
+                Err(e) => res = Err(e),
+            }
+        }
+        false
+    });
+    res.map(|()| marker)
+}
+
+/// Removes any `#[hazmat::skip]` attribute from `attrs`, and returns whether there was
+/// one.
+///
+/// Returns an error if the attribute is combined with a `#[hazmat::marker(..)]`.
+fn take_skip(attrs: &mut Vec<syn::Attribute>) -> syn::Result<bool> {
+    let mut skip = false;
+    let mut res = Ok(());
+    attrs.retain(|attr| {
+        if !is_hazmat_attr(attr, "skip") {
+            return true;
+        }
+        if skip {
+            res = Err(syn::Error::new_spanned(
+                attr,
+                "duplicate hazmat::skip attribute",
+            ));
+        } else if !attr.tokens.is_empty() {
+            res = Err(syn::Error::new_spanned(
+                attr,
+                "hazmat::skip doesn't take arguments",
+            ));
+        }
+        skip = true;
+        false
+    });
+    res?;
+
+    match attrs.iter().find(|attr| is_hazmat_attr(attr, "marker")) {
+        Some(marker) if skip => Err(syn::Error::new_spanned(
+            marker,
+            "hazmat::marker can't be applied to a method with hazmat::skip",
+        )),
+        _ => Ok(skip),
+    }
+}
+
+fn augment_trait(mut t: syn::ItemTrait, args: &SuitArgs) -> syn::Result<TokenStream> {
+    // These arguments configure the generated capability.
+    args.check_conflicts(
+        "cap_ty",
+        &[
+            "thread_local",
+            "pinned",
+            "emit_tests",
+            "doc_ctor",
+            "unsafe_ctor",
+            "require_explicit_cap",
+            "cap_default_impls",
+            "safe_ext",
+            "cap_impl",
+            "display",
+            "mint_via",
+            "from_seed",
+            "audit_callback",
+            "preset",
+            "derive",
+            "cap_module",
+            "cap_in_mod",
+        ],
+    )?;
+    args.check_conflicts("mint_via", &["safe_ext", "from_seed"])?;
+    args.check_conflicts("from_seed", &["safe_ext"])?;
+    args.check_conflicts("unsafe_ctor", &["safe_ext", "from_seed"])?;
+    args.check_conflicts(
+        "cap_default_impls",
+        &[
+            "preset",
+            "derive",
+            "cap_impl",
+            "display",
+            "emit_tests",
+            "from_seed",
+        ],
+    )?;
+    args.check_conflicts("cap_in_mod", &["generic_cap"])?;
+    check_ambient_conflicts(args)?;
+    check_generic_cap_conflicts(args)?;
+
+    // Create a name for the capability corresponding to this trait.
+    let cap_name = syn::Ident::new(&format!("{}Cap", t.ident), Span::call_site());
+    let cap_module = args.cap_module(&t.ident);
+    let cap_base: syn::Path = match &cap_module {
+        Some(module) => parse_quote!(#module::#cap_name),
+        None => parse_quote!(#cap_name),
+    };
+
+    // Generate the safe extension trait (if requested) from the unmodified trait.
+    let safe_ext = args
+        .safe_ext
+        .as_ref()
+        .map(|ext_name| safe_ext_trait(&t, &cap_base, ext_name, args));
+
+    // With `generic_cap`, the capability has the same generic parameters as the trait.
+    let cap_path: syn::Path = if args.generic_cap.is_some() {
+        let (_, ty_generics, _) = t.generics.split_for_impl();
+        parse_quote!(#cap_base #ty_generics)
+    } else {
+        cap_base.clone()
+    };
+
+    if args.require_explicit_cap.is_some() {
+        let note = format!(
+            "Implementations of this trait must write the `{}` parameter of each method \
+             explicitly; `#[hazmat::suit]` can't be applied to them.",
+            cap_name,
+        );
+        t.attrs.push(parse_quote!(#[doc = ""]));
+        t.attrs.push(parse_quote!(#[doc = #note]));
+    }
+
+    // Modify the trait to add the capability to each method as an argument. All other
+    // items (constants and types) are passed through unmodified.
+    let mut marked = false;
+    let mut expected_arities = vec![];
+    for item in &mut t.items {
+        // Macro invocations are expanded after this attribute, so any methods that they
+        // generate would silently lack the capability.
+        if let syn::TraitItem::Macro(mac) = item {
+            return Err(syn::Error::new_spanned(
+                mac,
+                "methods generated by macro invocations inside a #[hazmat::suit] trait \
+                 would not require the capability; expand the macro outside of the trait \
+                 instead",
+            ));
+        }
+
+        if let syn::TraitItem::Method(method) = item {
+            let method_name = format!("{}::{}", t.ident, method.sig.ident);
+            let arity = method.sig.inputs.len();
+            let skip = take_skip(&mut method.attrs)?;
+            if skip {
+                // Skipped methods don't require the capability.
+            } else if args.ambient.is_some() {
+                // Ambient capabilities are checked at runtime instead of being passed.
+                reject_ambient_marker(&mut method.attrs)?;
+                if let Some(body) = method.default.as_mut() {
+                    add_ambient_check(body, &cap_path, &method_name, args);
+                }
+            } else {
+                let marker = take_marker(&mut method.attrs)?;
+                let cap_ty = method_cap_ty(cap_path.clone(), marker.as_ref(), args)?;
+                marked |= marker.is_some();
+                push_cap_arg(&mut method.sig, cap_ty, args)?;
+            }
+
+            if args.verify_arity.is_some() {
+                // Ambient capabilities aren't passed as arguments.
+                let injected = usize::from(!skip && args.ambient.is_none());
+                expected_arities.push((method.sig.ident.clone(), arity + injected));
+            }
+
+            if let Some(body) = method.default.as_mut().filter(|_| args.log_usage.is_some()) {
+                *body = if method.sig.asyncness.is_some() {
+                    // An entered span can't be held across an `.await`, so the body is
+                    // instrumented instead.
+                    parse_quote!({
+                        ::tracing::Instrument::instrument(
+                            async move #body,
+                            ::tracing::debug_span!(#method_name),
+                        )
+                        .await
+                    })
+                } else {
+                    parse_quote!({
+                        let _span = ::tracing::debug_span!(#method_name).entered();
+                        #body
+                    })
+                };
+            }
+        }
+    }
+
+    let cap = match &args.cap_ty {
+        Some(cap_ty) => check_cap_ty(cap_ty, args),
+        None if args.ambient.is_some() => ambient_cap(&cap_name, args),
+        None => cap_struct(
+            &cap_name,
+            &t.vis,
+            marked,
+            args.generic_cap.as_ref().map(|_| &t.generics),
+            args,
+        ),
+    };
+
+    // Place the capability in its own module, so that glob imports of several crates'
+    // items don't make its name ambiguous.
+    let cap = match &cap_module {
+        Some(module) => quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            pub mod #module {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #cap
+            }
+        },
+        None => cap,
+    };
+
+    if let (Some(size), Some(cap_ty)) = (&args.cap_size_zero, &args.cap_ty) {
+        if mentions_self(cap_ty) {
+            return Err(syn::Error::new_spanned(
+                size,
+                "hazmat::suit(cap_size) can't be used with a cap_ty that mentions `Self`",
+            ));
+        }
+    }
+    let size_check = args.cap_size_zero.as_ref().map(|_| {
+        let core = args.core_path();
+        let cap_ty = match &args.cap_ty {
+            Some(cap_ty) => quote!(#cap_ty),
+            None => quote!(#cap_base),
+        };
+        quote! {
+            const _: () = assert!(
+                #core::mem::size_of::<#cap_ty>() == 0,
+                "hazmat::suit(cap_size = \"zero\") requires the capability to be zero-sized",
+            );
+        }
+    });
+
+    // Seal the trait with a supertrait that can't be named outside of the trait's module.
+    let sealed = (!args.sealed.is_empty()).then(|| {
+        let module = syn::Ident::new(&format!("__hazmat_sealed_{}", t.ident), Span::call_site());
+        let types = &args.sealed;
+        t.supertraits.push(parse_quote!(#module::Sealed));
+        t.colon_token.get_or_insert_with(Default::default);
+        quote! {
+            #[allow(non_snake_case)]
+            mod #module {
+                pub trait Sealed {}
+            }
+
+            #(impl #module::Sealed for #types {})*
+        }
+    });
+
+    // A proc macro can't add items to another module, so instead we check that the author
+    // has re-exported the capability from the requested one.
+    let cap_in_mod = args.cap_in_mod.as_ref().map(|module| {
+        quote! {
+            const _: () = {
+                fn cap_in_mod(cap: crate::#module::#cap_name) -> #cap_base {
+                    cap
+                }
+            };
+        }
+    });
+
+    let arity_checks = arity_checks(&t, &expected_arities);
+
+    Ok(quote! {
+        #cap
+        #size_check
+        #cap_in_mod
+        #sealed
+
+        #t
+
+        #arity_checks
+        #safe_ext
+    })
+}
+
+/// Generates a check for `verify_arity` that each method of the emitted trait `t` has the
+/// expected number of arguments.
+///
+/// The expected counts are taken from the trait before it was augmented, and the compiler
+/// checks them by coercing each method to a function pointer with that many arguments.
+/// Methods with type or const generic parameters (including `impl Trait` arguments) can't
+/// be named without their generic arguments, so they aren't checked.
+fn arity_checks(t: &syn::ItemTrait, expected: &[(syn::Ident, usize)]) -> TokenStream {
+    let trait_name = &t.ident;
+    let (_, ty_generics, _) = t.generics.split_for_impl();
+    let checks = t.items.iter().filter_map(|item| match item {
+        syn::TraitItem::Method(method) => {
+            let sig = &method.sig;
+            let (_, arity) = expected.iter().find(|(name, _)| *name == sig.ident)?;
+            if sig.generics.type_params().next().is_some()
+                || sig.generics.const_params().next().is_some()
+                || sig.inputs.iter().any(|arg| match arg {
+                    syn::FnArg::Typed(arg) => mentions_impl_trait(&arg.ty),
+                    syn::FnArg::Receiver(_) => false,
+                })
+            {
+                return None;
+            }
+
+            // The check is a function generic over an implementation `__T` of the trait,
+            // so `Self` in the trait's and method's bounds is replaced with `__T`.
+            let mut generics = t.generics.clone();
+            for param in &mut generics.params {
+                match param {
+                    syn::GenericParam::Type(param) => {
+                        param.eq_token = None;
+                        param.default = None;
+                    }
+                    syn::GenericParam::Const(param) => {
+                        param.eq_token = None;
+                        param.default = None;
+                    }
+                    syn::GenericParam::Lifetime(_) => (),
+                }
+            }
+            // Lifetime parameters must come first.
+            let lifetimes = sig.generics.lifetimes().chain(generics.lifetimes());
+            let params = generics
+                .params
+                .iter()
+                .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)));
+            let predicates = generics
+                .where_clause
+                .iter()
+                .chain(&sig.generics.where_clause)
+                .flat_map(|w| &w.predicates)
+                .map(|predicate| replace_self(quote!(#predicate)));
+            // The inputs of the check are those of the method, so that it has the same
+            // implied bounds.
+            let inputs = sig.inputs.iter().map(|arg| match arg {
+                syn::FnArg::Receiver(r) => {
+                    let (and, lifetime) = match &r.reference {
+                        Some((and, lifetime)) => (Some(and), lifetime.as_ref()),
+                        None => (None, None),
+                    };
+                    let mutability = r.reference.as_ref().and(r.mutability);
+                    quote!(_: #and #lifetime #mutability __T)
+                }
+                syn::FnArg::Typed(arg) => {
+                    let ty = &arg.ty;
+                    let ty = replace_self(quote!(#ty));
+                    quote!(_: #ty)
+                }
+            });
+            let unsafety = &sig.unsafety;
+            let abi = &sig.abi;
+            let method_name = &sig.ident;
+            // The check is only emitted if the method is.
+            let cfgs = method.attrs.iter().filter(|attr| attr.path.is_ident("cfg"));
+            let placeholders = (0..*arity).map(|_| quote!(_));
+            Some(quote! {
+                #(#cfgs)*
+                const _: () = {
+                    #[allow(dead_code, clippy::extra_unused_lifetimes)]
+                    fn check<#(#lifetimes,)* __T, #(#params,)*>(#(#inputs),*)
+                    where
+                        __T: #trait_name #ty_generics,
+                        #(#predicates,)*
+                    {
+                        let _: #unsafety #abi fn(#(#placeholders),*) -> _ =
+                            <__T as #trait_name #ty_generics>::#method_name;
+                    }
+                };
+            })
+        }
+        _ => None,
+    });
+    quote!(#(#checks)*)
+}
+
+/// Returns whether the given type contains an `impl Trait`.
+fn mentions_impl_trait(ty: &syn::Type) -> bool {
+    struct MentionsImplTrait(bool);
+
+    impl<'ast> Visit<'ast> for MentionsImplTrait {
+        fn visit_type_impl_trait(&mut self, _: &'ast syn::TypeImplTrait) {
+            self.0 = true;
+        }
+    }
+
+    let mut visitor = MentionsImplTrait(false);
+    visitor.visit_type(ty);
+    visitor.0
+}
+
+/// Replaces each `Self` in the given tokens with `__T`.
+fn replace_self(tokens: TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            proc_macro2::TokenTree::Ident(ident) if ident == "Self" => {
+                proc_macro2::TokenTree::Ident(syn::Ident::new("__T", ident.span()))
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut replaced =
+                    proc_macro2::Group::new(group.delimiter(), replace_self(group.stream()));
+                replaced.set_span(group.span());
+                proc_macro2::TokenTree::Group(replaced)
+            }
+            tt => tt,
+        })
+        .collect()
+}
+
+/// Returns the type of the capability argument for a method with the given marker.
+///
+/// `cap` is the path to the generated capability; it is ignored if an existing capability
+/// type has been provided with `cap_ty`.
+fn method_cap_ty(
+    cap: syn::Path,
+    marker: Option<&syn::Type>,
+    args: &SuitArgs,
+) -> syn::Result<syn::Type> {
+    match (&args.cap_ty, marker) {
+        (Some(cap_ty), None) => Ok(cap_ty.clone()),
+        (Some(_), Some(marker)) => Err(syn::Error::new_spanned(
+            marker,
+            "hazmat::marker can't be used with hazmat::suit(cap_ty)",
+        )),
+        (None, Some(marker)) if args.generic_cap.is_some() => Err(syn::Error::new_spanned(
+            marker,
+            "hazmat::marker can't be used with hazmat::suit(generic_cap)",
+        )),
+        (None, Some(marker)) => Ok(parse_quote!(#cap<#marker>)),
+        (None, None) => Ok(parse_quote!(#cap)),
+    }
+}
+
+/// Returns an error if `ambient` was combined with arguments that configure the
+/// capability argument or the generated capability.
+fn check_ambient_conflicts(args: &SuitArgs) -> syn::Result<()> {
+    args.check_conflicts(
+        "ambient",
+        &[
+            "thread_local",
+            "pinned",
+            "emit_tests",
+            "doc_ctor",
+            "unsafe_ctor",
+            "require_explicit_cap",
+            "cap_default_impls",
+            "safe_ext",
+            "cap_impl",
+            "display",
+            "mint_via",
+            "from_seed",
+            "audit_callback",
+            "preset",
+            "derive",
+            "cap_size",
+            "by_ref",
+            "position",
+            "generic_cap",
+            "cap_ty",
+            "cap_module",
+            "cap_in_mod",
+        ],
+    )
+}
+
+/// Returns an error if `generic_cap` was combined with arguments that require a
+/// non-generic capability.
+fn check_generic_cap_conflicts(args: &SuitArgs) -> syn::Result<()> {
+    args.check_conflicts(
+        "generic_cap",
+        &["emit_tests", "cap_size", "cap_ty", "require_explicit_cap"],
+    )
+}
+
+/// Returns an error if a method of an `ambient` trait or impl is marked.
+fn reject_ambient_marker(attrs: &mut Vec<syn::Attribute>) -> syn::Result<()> {
+    match take_marker(attrs)? {
+        Some(marker) => Err(syn::Error::new_spanned(
+            marker,
+            "hazmat::marker can't be used with hazmat::suit(ambient)",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Prefixes a method body with a runtime check that the ambient capability is present.
+fn add_ambient_check(body: &mut syn::Block, cap: &syn::Path, method_name: &str, args: &SuitArgs) {
+    let core = args.core_path();
+    let cap_name = &cap.segments.last().unwrap().ident;
+    let msg = format!(
+        "`{}` must be called within `{}::with_capability`",
+        method_name, cap_name
+    );
+    *body = parse_quote!({
+        #core::assert!(#cap::is_present(), #msg);
+        #body
+    });
+}
+
+/// Generates an ambient capability, which is present on a thread for the duration of
+/// `with_capability` instead of being passed as an argument.
+fn ambient_cap(cap_name: &syn::Ident, args: &SuitArgs) -> TokenStream {
+    let core = args.core_path();
+    quote! {
+        #[non_exhaustive]
+        pub struct #cap_name;
+
+        impl #cap_name {
+            /// Runs `f` with this capability present on the current thread.
+            #[allow(dead_code)]
+            pub(crate) fn with_capability<R>(f: impl #core::ops::FnOnce() -> R) -> R {
+                // Restore the previous depth even if `f` panics.
+                struct Reset;
+                impl #core::ops::Drop for Reset {
+                    fn drop(&mut self) {
+                        #cap_name::depth().with(|d| d.set(d.get() - 1));
+                    }
+                }
+
+                Self::depth().with(|d| d.set(d.get() + 1));
+                let _reset = Reset;
+                f()
+            }
+
+            /// Returns whether this capability is present on the current thread.
+            pub fn is_present() -> bool {
+                Self::depth().with(|d| d.get() > 0)
+            }
+
+            /// The number of nested `with_capability` scopes on the current thread.
+            fn depth() -> &'static ::std::thread::LocalKey<#core::cell::Cell<usize>> {
+                ::std::thread_local! {
+                    static DEPTH: #core::cell::Cell<usize> = const { #core::cell::Cell::new(0) };
+                }
+                &DEPTH
+            }
+        }
+    }
+}
+
+/// Generates a usage of an existing capability type, so that an invalid path is reported
+/// once at the attribute.
+fn check_cap_ty(cap_ty: &syn::Type, args: &SuitArgs) -> TokenStream {
+    // `Self` can't be named outside of the trait, but the method signatures already name
+    // the type, so it must exist anyway.
+    if mentions_self(cap_ty) {
+        return quote!();
+    }
+
+    let core = args.core_path();
+    quote! {
+        const _: fn() = || {
+            let _: #core::marker::PhantomData<#cap_ty>;
+        };
+    }
+}
+
+/// Appends the capability argument, of type `cap_ty`, to the given method signature.
+fn push_cap_arg(sig: &mut syn::Signature, cap_ty: syn::Type, args: &SuitArgs) -> syn::Result<()> {
+    let cap_ty = if args.by_ref.is_some() {
+        check_by_ref_elision(sig)?;
+        parse_quote!(&#cap_ty)
+    } else {
+        cap_ty
+    };
+
+    let cap_arg = syn::PatType {
+        attrs: vec![],
+        pat: parse_quote!(cap),
+        colon_token: parse_quote!(:),
+        ty: Box::new(cap_ty),
+    };
+    let index = args.cap_index(&sig.inputs);
+    sig.inputs.insert(index, cap_arg.into());
+    Ok(())
+}
+
+/// Checks that passing the capability by reference won't change how the elided lifetimes
+/// in the signature's return type are resolved.
+///
+/// Elided output lifetimes are taken from a `&self` receiver if there is one (including
+/// typed receivers like `self: Pin<&mut Self>` that contain a `&Self` or `&mut Self`);
+/// otherwise they are only valid if there is exactly one input lifetime, which an extra
+/// `&Cap` argument would make ambiguous.
+fn check_by_ref_elision(sig: &syn::Signature) -> syn::Result<()> {
+    let has_ref_receiver = sig.inputs.first().is_some_and(|arg| match arg {
+        syn::FnArg::Receiver(r) => r.reference.is_some(),
+        syn::FnArg::Typed(arg) => {
+            matches!(&*arg.pat, syn::Pat::Ident(pat) if pat.ident == "self")
+                && (matches!(&*arg.ty, syn::Type::Reference(_)) || contains_self_ref(&arg.ty))
+        }
+    });
+
+    if let syn::ReturnType::Type(_, ty) = &sig.output {
+        let mut visitor = ElidedLifetimes(false);
+        visitor.visit_type(ty);
+        if visitor.0 && !has_ref_receiver {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "hazmat::suit(by_ref) would make the elided lifetimes in this return type \
+                 ambiguous; name them explicitly",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the given type contains a `&Self` or `&mut Self`.
+fn contains_self_ref(ty: &syn::Type) -> bool {
+    struct SelfRef(bool);
+
+    impl<'ast> Visit<'ast> for SelfRef {
+        fn visit_type_reference(&mut self, r: &'ast syn::TypeReference) {
+            if matches!(&*r.elem, syn::Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self"))
+            {
+                self.0 = true;
+            }
+            visit::visit_type_reference(self, r);
+        }
+    }
+
+    let mut visitor = SelfRef(false);
+    visitor.visit_type(ty);
+    visitor.0
+}
+
+/// A visitor that detects elided lifetimes that would be resolved using the lifetimes of
+/// the enclosing function's arguments.
+struct ElidedLifetimes(bool);
+
+impl<'ast> Visit<'ast> for ElidedLifetimes {
+    fn visit_type_reference(&mut self, r: &'ast syn::TypeReference) {
+        if r.lifetime.is_none() {
+            self.0 = true;
+        }
+        visit::visit_type_reference(self, r);
+    }
+
+    fn visit_lifetime(&mut self, l: &'ast syn::Lifetime) {
+        if l.ident == "_" {
+            self.0 = true;
+        }
+    }
+
+    // Function pointers and `Fn` traits have their own elision scopes.
+    fn visit_type_bare_fn(&mut self, _: &'ast syn::TypeBareFn) {}
+    fn visit_parenthesized_generic_arguments(
+        &mut self,
+        _: &'ast syn::ParenthesizedGenericArguments,
+    ) {
+    }
+}
+
+/// Returns whether the given type mentions `Self`.
+fn mentions_self(ty: &syn::Type) -> bool {
+    struct MentionsSelf(bool);
+
+    impl<'ast> Visit<'ast> for MentionsSelf {
+        fn visit_path_segment(&mut self, segment: &'ast syn::PathSegment) {
+            if segment.ident == "Self" {
+                self.0 = true;
+            }
+            visit::visit_path_segment(self, segment);
+        }
+    }
+
+    let mut visitor = MentionsSelf(false);
+    visitor.visit_type(ty);
+    visitor.0
+}
+
+/// Generates an extension trait for `t` (which must not yet have been augmented) with a
+/// blanket implementation, that exposes each method of `t` without a capability argument.
+fn safe_ext_trait(
+    t: &syn::ItemTrait,
+    cap_path: &syn::Path,
+    ext_name: &syn::Ident,
+    args: &SuitArgs,
+) -> TokenStream {
+    let vis = &t.vis;
+    let trait_name = &t.ident;
+    let (impl_generics, ty_generics, where_clause) = t.generics.split_for_impl();
+
+    // Skipped methods can already be called without a capability.
+    let methods = t.items.iter().filter_map(|item| match item {
+        syn::TraitItem::Method(method)
+            if !method.attrs.iter().any(|attr| is_hazmat_attr(attr, "skip")) =>
+        {
+            Some(method)
+        }
+        _ => None,
+    });
+    let wrappers = methods.map(|method| {
+        let name = &method.sig.ident;
+        let mut sig = method.sig.clone();
+        sig.ident = syn::Ident::new(&format!("{}_safe", name), name.span());
+
+        // Bind every argument to an identifier, so that they can be forwarded.
+        let cap_index = args.cap_index(&sig.inputs);
+        let mut call_args: Vec<_> = sig
+            .inputs
+            .iter_mut()
+            .enumerate()
+            .map(|(i, arg)| match arg {
+                syn::FnArg::Receiver(_) => quote!(self),
+                syn::FnArg::Typed(arg) => match &mut *arg.pat {
+                    syn::Pat::Ident(pat) if pat.subpat.is_none() => {
+                        pat.by_ref = None;
+                        pat.mutability = None;
+                        let ident = &pat.ident;
+                        quote!(#ident)
+                    }
+                    pat => {
+                        let ident = syn::Ident::new(&format!("arg{}", i), Span::call_site());
+                        *pat = parse_quote!(#ident);
+                        quote!(#ident)
+                    }
+                },
+            })
+            .collect();
+        call_args.insert(
+            cap_index,
+            if args.by_ref.is_some() {
+                quote!(&#cap_path::new())
+            } else {
+                quote!(#cap_path::new())
+            },
+        );
+        let mut call = quote!(<Self as #trait_name #ty_generics>::#name(#(#call_args),*));
+        if sig.asyncness.is_some() {
+            call = quote!(#call.await);
+        }
+
+        let attrs = method
+            .attrs
+            .iter()
+            .filter(|attr| !is_hazmat_attr(attr, "marker"));
+        quote! {
+            #(#attrs)*
+            #sig {
+                #call
+            }
+        }
+    });
+
+    // Add a type parameter for the blanket implementation.
+    let mut blanket_generics = t.generics.clone();
+    blanket_generics
+        .params
+        .push(parse_quote!(__T: #trait_name #ty_generics));
+    let (blanket_impl_generics, _, blanket_where_clause) = blanket_generics.split_for_impl();
+
+    let doc = format!(
+        "Safe wrappers around the methods of [`{}`], which provide the capability internally.",
+        trait_name,
+    );
+
+    quote! {
+        #[doc = #doc]
+        #vis trait #ext_name #impl_generics: #trait_name #ty_generics + Sized #where_clause {
+            #(#wrappers)*
+        }
+
+        // Keep the blanket impl out of trait-resolution errors, which should instead refer
+        // to the extension trait itself.
+        #[diagnostic::do_not_recommend]
+        impl #blanket_impl_generics #ext_name #ty_generics for __T #blanket_where_clause {}
+    }
+}
+
+/// Generates the capability type for a trait, along with its crate-internal constructor.
+///
+/// If `marked` is true, the capability is made generic over a marker type.
+fn cap_struct(
+    cap_name: &syn::Ident,
+    trait_vis: &syn::Visibility,
+    marked: bool,
+    generics: Option<&syn::Generics>,
+    args: &SuitArgs,
+) -> TokenStream {
+    let core = args.core_path();
+
+    // The private fields of the capability, and their initializers.
+    let mut fields: Vec<(TokenStream, TokenStream)> = vec![];
+    let mut impls = vec![];
+
+    // The generic parameters of the capability.
+    let (def_generics, impl_generics, ty_generics) = if marked {
+        fields.push((
+            quote!(#core::marker::PhantomData<fn() -> M>),
+            quote!(#core::marker::PhantomData),
+        ));
+        (quote!(<M = ()>), quote!(<M>), quote!(<M>))
+    } else if let Some(generics) = generics {
+        // The capability doesn't need the bounds of the trait's parameters. It keeps their
+        // defaults, so that impls relying on a default name the same capability, unless a
+        // default mentions `Self` (which would refer to the capability instead).
+        let mut generics = generics.clone();
+        // The capability keeps `?Sized` relaxations, from either the parameter or the
+        // where clause, because without them its parameters would have to be `Sized`.
+        let is_maybe = |bound: &syn::TypeParamBound| {
+            matches!(
+                bound,
+                syn::TypeParamBound::Trait(syn::TraitBound {
+                    modifier: syn::TraitBoundModifier::Maybe(_),
+                    ..
+                })
+            )
+        };
+        let mut maybe_sized: Vec<syn::Ident> = generics
+            .type_params()
+            .filter(|param| param.bounds.iter().any(is_maybe))
+            .map(|param| param.ident.clone())
+            .collect();
+        if let Some(where_clause) = generics.where_clause.take() {
+            for predicate in where_clause.predicates {
+                if let syn::WherePredicate::Type(p) = predicate {
+                    if let syn::Type::Path(ty) = &p.bounded_ty {
+                        if let Some(ident) = ty.path.get_ident() {
+                            if ty.qself.is_none() && p.bounds.iter().any(is_maybe) {
+                                maybe_sized.push(ident.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        // (Const parameter defaults can't mention `Self`.)
+        let keep_defaults = !generics.params.iter().any(|param| match param {
+            syn::GenericParam::Type(param) => param.default.as_ref().is_some_and(mentions_self),
+            _ => false,
+        });
+        let mut phantom = vec![];
+        for param in &mut generics.params {
+            match param {
+                syn::GenericParam::Type(param) => {
+                    param.bounds.clear();
+                    if maybe_sized.contains(&param.ident) {
+                        param.bounds.push(parse_quote!(?Sized));
+                    }
+                    param.colon_token = param.bounds.first().map(|_| Default::default());
+                    if !keep_defaults {
+                        param.eq_token = None;
+                        param.default = None;
+                    }
+                    let ident = &param.ident;
+                    phantom.push(quote!(#ident));
+                }
+                syn::GenericParam::Lifetime(param) => {
+                    param.bounds.clear();
+                    let lifetime = &param.lifetime;
+                    phantom.push(quote!(&#lifetime ()));
+                }
+                syn::GenericParam::Const(param) => {
+                    if !keep_defaults {
+                        param.eq_token = None;
+                        param.default = None;
+                    }
+                }
+            }
+        }
+        // Type and lifetime parameters must be used.
+        if !phantom.is_empty() {
+            fields.push((
+                quote!(#core::marker::PhantomData<fn() -> (#(#phantom,)*)>),
+                quote!(#core::marker::PhantomData),
+            ));
+        }
+        let (impl_generics, ty_generics, _) = generics.split_for_impl();
+        (
+            quote!(#generics),
+            quote!(#impl_generics),
+            quote!(#ty_generics),
+        )
+    } else {
+        (quote!(), quote!(), quote!())
+    };
+    let cap = quote!(#cap_name #ty_generics);
+
+    if args.thread_local.is_some() {
+        if args.thread_local_negative_impls.is_some() {
+            impls.push(quote! {
+                impl #impl_generics !Send for #cap {}
+                impl #impl_generics !Sync for #cap {}
+            });
+        } else {
+            fields.push((
+                quote!(#core::marker::PhantomData<*const ()>),
+                quote!(#core::marker::PhantomData),
+            ));
+        }
+    } else {
+        // Catch any future change that would accidentally make the capability `!Send` or
+        // `!Sync`.
+        impls.push(quote! {
+            const _: () = {
+                #[allow(dead_code)]
+                fn check #impl_generics () {
+                    fn assert_send_sync<T: #core::marker::Send + #core::marker::Sync>() {}
+                    assert_send_sync::<#cap>();
+                }
+            };
+        });
+    }
+
+    if args.pinned.is_some() {
+        fields.push((
+            quote!(#core::marker::PhantomPinned),
+            quote!(#core::marker::PhantomPinned),
+        ));
+    }
+
+    // Capabilities with restricted construction need a private field, so that they can't
+    // be constructed by a struct expression.
+    if fields.is_empty() && args.restricts_construction() {
+        fields.push((quote!(()), quote!(())));
+    }
+
+    // Give the trait author a doctest showing that downstream crates can't construct the
+    // capability. `module_path!()` expands to the path of the module that defines it. The
+    // capability of a non-`pub` trait can't be named downstream at all, so the doctest
+    // would fail for the wrong reason.
+    let ctor_name = args.ctor_name();
+    let doc = if args.emit_tests.is_some() && matches!(trait_vis, syn::Visibility::Public(_)) {
+        let cap_str = cap_name.to_string();
+        let ctor_str = ctor_name.to_string();
+        quote! {
+            #[doc = "Downstream crates can't construct this capability:"]
+            #[doc = ""]
+            #[doc = "```compile_fail"]
+            #[doc = #core::concat!("let _cap = ", #core::module_path!(), "::", #cap_str, "::", #ctor_str, "();")]
+            #[doc = "```"]
+        }
+    } else {
+        quote!()
+    };
+
+    // Deriving traits for marked capabilities would add bounds on the marker type (such as
+    // `M: Clone`), so the traits that don't need them are implemented directly.
+    let (manual, derives): (Vec<_>, Vec<_>) = args.cap_derives().into_iter().partition(|path| {
+        marked
+            && path.segments.last().is_some_and(|s| {
+                [
+                    "Debug",
+                    "Clone",
+                    "Copy",
+                    "PartialEq",
+                    "Eq",
+                    "PartialOrd",
+                    "Ord",
+                    "Hash",
+                ]
+                .contains(&&*s.ident.to_string())
+            })
+    });
+    let manual_has = |name: &str| {
+        manual
+            .iter()
+            .any(|path| path.segments.last().unwrap().ident == name)
+    };
+    let (copy, ord) = (manual_has("Copy"), manual_has("Ord"));
+    let field_indices: Vec<_> = (0..fields.len()).map(syn::Index::from).collect();
+    for path in manual {
+        // The derive macros of these traits aren't necessarily in scope as traits, so the
+        // traits are named by their paths in `core`.
+        let name = &path.segments.last().unwrap().ident;
+        let module = match &*name.to_string() {
+            "Debug" => quote!(fmt),
+            "Clone" => quote!(clone),
+            "Copy" => quote!(marker),
+            "Hash" => quote!(hash),
+            _ => quote!(cmp),
+        };
+        let cap_str = cap_name.to_string();
+        let body = match &*name.to_string() {
+            "Debug" => quote! {
+                fn fmt(&self, f: &mut #core::fmt::Formatter<'_>) -> #core::fmt::Result {
+                    f.debug_tuple(#cap_str)
+                        #(.field(&self.#field_indices))*
+                        .finish()
+                }
+            },
+            // Capabilities are zero-sized, so they are always equal.
+            "PartialEq" => quote! {
+                fn eq(&self, _other: &Self) -> bool {
+                    true
+                }
+            },
+            "PartialOrd" if ord => quote! {
+                fn partial_cmp(&self, other: &Self) -> #core::option::Option<#core::cmp::Ordering> {
+                    #core::option::Option::Some(#core::cmp::Ord::cmp(self, other))
+                }
+            },
+            "PartialOrd" => quote! {
+                fn partial_cmp(&self, _other: &Self) -> #core::option::Option<#core::cmp::Ordering> {
+                    #core::option::Option::Some(#core::cmp::Ordering::Equal)
+                }
+            },
+            "Ord" => quote! {
+                fn cmp(&self, _other: &Self) -> #core::cmp::Ordering {
+                    #core::cmp::Ordering::Equal
+                }
+            },
+            // Nothing is hashed, which is consistent with `PartialEq`.
+            "Hash" => quote! {
+                fn hash<H: #core::hash::Hasher>(&self, _state: &mut H) {}
+            },
+            "Clone" if copy => quote! {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            },
+            "Clone" => quote! {
+                fn clone(&self) -> Self {
+                    Self(#(#core::clone::Clone::clone(&self.#field_indices)),*)
+                }
+            },
+            _ => quote!(),
+        };
+        impls.push(quote! {
+            impl #impl_generics #core::#module::#name for #cap {
+                #body
+            }
+        });
+    }
+    let derive_attr = if derives.is_empty() {
+        quote!()
+    } else {
+        quote!(#[derive(#(#derives),*)])
+    };
+
+    let (def, body) = if fields.is_empty() {
+        (
+            quote! {
+                #doc
+                #derive_attr
+                #[non_exhaustive]
+                pub struct #cap_name #def_generics;
+            },
+            quote!(#cap_name),
+        )
+    } else {
+        // Private fields prevent construction outside of the current module, so the
+        // capability doesn't need to be marked `#[non_exhaustive]`.
+        let (tys, inits): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        (
+            quote! {
+                #doc
+                #derive_attr
+                pub struct #cap_name #def_generics(#(#tys),*);
+            },
+            quote!(#cap_name(#(#inits),*)),
+        )
+    };
+
+    let ctor_args = match args.mint_guard() {
+        Some(guard) => quote!(_guard: &#guard),
+        None => quote!(),
+    };
+    // The audit callback is told where each capability was constructed.
+    let (ctor_attrs, constness, body) = match &args.audit_callback {
+        Some(callback) => {
+            let cap_str = cap_name.to_string();
+            (
+                quote!(#[track_caller]),
+                quote!(),
+                quote! {
+                    #callback(#cap_str, #core::panic::Location::caller());
+                    #body
+                },
+            )
+        }
+        None => (quote!(), quote!(const), body),
+    };
+    // With `unsafe_ctor`, minting a capability is flagged as hazardous at each call site.
+    let (ctor_attrs, unsafety) = if args.unsafe_ctor.is_some() {
+        (
+            quote! {
+                #[doc = ""]
+                #[doc = "# Safety"]
+                #[doc = ""]
+                #[doc = "The caller must be authorized to use the methods that require this"]
+                #[doc = "capability."]
+                #ctor_attrs
+            },
+            quote!(unsafe),
+        )
+    } else {
+        (ctor_attrs, quote!())
+    };
+    let ctor = |vis: TokenStream| {
+        if marked {
+            quote! {
+                #ctor_attrs
+                #vis #constness #unsafety fn #ctor_name<M>(#ctor_args) -> #cap_name<M> {
+                    #body
+                }
+            }
+        } else {
+            quote! {
+                #ctor_attrs
+                #vis #constness #unsafety fn #ctor_name(#ctor_args) -> Self {
+                    #body
+                }
+            }
+        }
+    };
+    let ctor_doc = if args.cap_default_impls_none.is_some() {
+        quote!()
+    } else {
+        quote! {
+            /// Constructs this capability.
+        }
+    };
+    // With `doc_ctor`, rustdoc renders the constructor as public, so that documentation
+    // can show how the capability would be passed. Real builds keep it crate-internal.
+    let ctor = if args.doc_ctor.is_some() {
+        let doc_ctor = ctor(quote!(#[cfg(doc)] pub));
+        let ctor = ctor(quote!(#[cfg(not(doc))] pub(crate)));
+        quote! {
+            #doc_ctor
+
+            #ctor_doc
+            #[allow(dead_code)]
+            #ctor
+        }
+    } else {
+        ctor(quote!(pub(crate)))
+    };
+
+    // With `from_seed`, capabilities can also be minted with `From<&Seed>`.
+    if let Some(seed) = &args.from_seed {
+        impls.push(quote! {
+            impl #impl_generics #core::convert::From<&#seed> for #cap {
+                #ctor_attrs
+                fn from(seed: &#seed) -> Self {
+                    #cap_name::new(seed)
+                }
+            }
+        });
+    }
+
+    // The impl helper checks this constant, so that traits can forbid its use.
+    let value = match &args.require_explicit_cap {
+        Some(_) => {
+            let msg = format!(
+                "{} requires implementations to write the capability parameter \
+                 explicitly; remove #[hazmat::suit] from this impl and add the \
+                 parameter to each method",
+                cap_name.to_string().strip_suffix("Cap").unwrap(),
+            );
+            quote!(#core::panic!(#msg))
+        }
+        None => quote!(()),
+    };
+    impls.push(quote! {
+        impl #impl_generics #cap {
+            #[doc(hidden)]
+            pub const __HAZMAT_IMPL_HELPER: () = #value;
+        }
+    });
+
+    // Capabilities with restricted construction can only be obtained from the constructor.
+    if !args.restricts_construction() && args.cap_default_impls_none.is_none() {
+        impls.push(quote! {
+            impl #impl_generics #cap {
+                /// An instance of this capability, for use in const contexts.
+                #[allow(dead_code)]
+                pub(crate) const INSTANCE: Self = #body;
+            }
+        });
+    }
+
+    for marker_trait in &args.cap_impls {
+        impls.push(quote! {
+            impl #impl_generics #marker_trait for #cap {}
+        });
+    }
+
+    if let Some(label) = &args.display {
+        impls.push(quote! {
+            impl #impl_generics #core::fmt::Display for #cap {
+                fn fmt(&self, f: &mut #core::fmt::Formatter<'_>) -> #core::fmt::Result {
+                    f.pad(#label)
+                }
+            }
+        });
+    }
+
+    if args.emit_tests.is_some() {
+        // Capabilities minted via a guard can't be constructed without one.
+        let constructible_test = if args.mint_guard().is_none() {
+            quote! {
+                #[test]
+                fn cap_is_constructible() {
+                    let _cap: super::#cap_name = #unsafety { super::#cap_name::#ctor_name() };
+                }
+            }
+        } else {
+            quote!()
+        };
+
+        let mod_name = syn::Ident::new(&format!("__hazmat_tests_{}", cap_name), Span::call_site());
+        impls.push(quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod #mod_name {
+                #[test]
+                fn cap_is_zero_sized() {
+                    assert_eq!(#core::mem::size_of::<super::#cap_name>(), 0);
+                }
+
+                #constructible_test
+            }
+        });
+    }
+
+    // Marked capabilities have a single constructor that is generic over the marker.
+    let (ctor_impl_generics, ctor_self) = if marked {
+        (quote!(), quote!(#cap_name))
+    } else {
+        (impl_generics, cap)
+    };
+
+    quote! {
+        #def
+
+        #(#impls)*
+
+        impl #ctor_impl_generics #ctor_self {
+            #ctor_doc
+            #[allow(dead_code)]
+            #ctor
+        }
+    }
+}
+
+fn augment_trait_impl(mut t: syn::ItemImpl, args: &SuitArgs) -> syn::Result<TokenStream> {
+    // Create a name for the capability corresponding to this trait.
+    let trait_path = &t.trait_.as_ref().unwrap().1;
+    let trait_name = &trait_path.segments.last().unwrap().ident;
+    let cap_name = syn::Ident::new(&format!("{}Cap", trait_name), Span::call_site());
+    let cap_path = {
+        let mut p = trait_path.clone();
+        let trait_segment = p.segments.pop().unwrap().into_value();
+        if let Some(module) = args.cap_module(trait_name) {
+            p.segments.push(module.into());
+        }
+        p.segments.push(syn::PathSegment {
+            ident: cap_name,
+            // With `generic_cap`, the capability takes the same generic arguments as the
+            // trait.
+            arguments: if args.generic_cap.is_some() {
+                trait_segment.arguments
+            } else {
+                syn::PathArguments::None
+            },
+        });
+        p
+    };
+
+    check_ambient_conflicts(args)?;
+    check_generic_cap_conflicts(args)?;
+
+    // Modify the trait implementation to add the capability to each method. All other
+    // items are passed through unmodified.
+    for item in &mut t.items {
+        if let syn::ImplItem::Method(method) = item {
+            if take_skip(&mut method.attrs)? {
+                // Skipped methods don't require the capability.
+            } else if args.ambient.is_some() {
+                reject_ambient_marker(&mut method.attrs)?;
+                let method_name = format!("{}::{}", trait_name, method.sig.ident);
+                add_ambient_check(&mut method.block, &cap_path, &method_name, args);
+            } else {
+                let marker = take_marker(&mut method.attrs)?;
+                let cap_ty = method_cap_ty(cap_path.clone(), marker.as_ref(), args)?;
+                push_cap_arg(&mut method.sig, cap_ty, args)?;
+            }
+        }
+    }
+
+    // Check that the trait allows its impls to use this macro. A generic capability can
+    // mention the impl's generic parameters, so it is checked within a generic function.
+    let helper_check = if args.ambient.is_some() || args.cap_ty.is_some() {
+        quote!()
+    } else if args.generic_cap.is_some() && !t.generics.params.is_empty() {
+        let (impl_generics, _, where_clause) = t.generics.split_for_impl();
+        quote! {
+            const _: () = {
+                #[allow(dead_code)]
+                fn check #impl_generics () #where_clause {
+                    <#cap_path>::__HAZMAT_IMPL_HELPER
+                }
+            };
+        }
+    } else {
+        quote!(const _: () = <#cap_path>::__HAZMAT_IMPL_HELPER;)
+    };
+
+    Ok(quote! {
+        #t
+        #helper_check
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::{augment_trait, augment_trait_impl, check_output, SuitArgs};
+
+    /// Returns the attributes of the method named `sign_raw` in the given code.
+    fn sign_raw_attrs(tokens: proc_macro2::TokenStream) -> Vec<String> {
+        let file: syn::File = syn::parse2(tokens).unwrap();
+        let attrs = file
+            .items
+            .into_iter()
+            .flat_map(|item| match item {
+                syn::Item::Trait(t) => t
+                    .items
+                    .into_iter()
+                    .filter_map(|item| match item {
+                        syn::TraitItem::Method(m) if m.sig.ident == "sign_raw" => Some(m.attrs),
+                        _ => None,
+                    })
+                    .collect(),
+                syn::Item::Impl(i) => i
+                    .items
+                    .into_iter()
+                    .filter_map(|item| match item {
+                        syn::ImplItem::Method(m) if m.sig.ident == "sign_raw" => Some(m.attrs),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => vec![],
+            })
+            .next()
+            .unwrap();
+        attrs.iter().map(|a| quote!(#a).to_string()).collect()
+    }
+
+    #[test]
+    fn check_output_passes_valid_code() {
+        let tokens = quote! {
+            pub struct RawCap;
+            pub trait Raw {
+                fn raw(&self, cap: RawCap);
+            }
+        };
+        assert_eq!(check_output(tokens.clone()).to_string(), tokens.to_string());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn check_output_reports_invalid_code() {
+        let tokens = check_output(quote!(pub trait Raw { fn raw(&self, cap RawCap); }));
+        assert!(tokens.to_string().contains("compile_error"));
+        assert!(tokens.to_string().contains("this is a bug in hazmat"));
+    }
+
+    #[test]
+    fn doc_attributes_are_preserved() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Sign {
+                /// Signs the message.
+                #[doc(alias = "sign")]
+                ///
+                /// Never reuse the nonce.
+                #[doc(alias("sig", "signature"))]
+                fn sign_raw(&self, msg: &[u8]) -> u32;
+            }
+        };
+        let expected = sign_raw_attrs(quote!(#t));
+        assert_eq!(expected.len(), 5);
+        let tokens = augment_trait(t, &SuitArgs::default()).unwrap();
+        assert_eq!(sign_raw_attrs(tokens), expected);
+
+        let i: syn::ItemImpl = parse_quote! {
+            impl Sign for Key {
+                /// Signs the message.
+                #[doc(alias = "sign")]
+                ///
+                /// Never reuse the nonce.
+                #[doc(alias("sig", "signature"))]
+                fn sign_raw(&self, msg: &[u8]) -> u32 {
+                    0
+                }
+            }
+        };
+        let expected = sign_raw_attrs(quote!(#i));
+        let tokens = augment_trait_impl(i, &SuitArgs::default()).unwrap();
+        assert_eq!(sign_raw_attrs(tokens), expected);
+    }
+
+    /// Returns the documentation of the struct named `name` in the given code, one line
+    /// per `#[doc]` attribute. Values that aren't string literals are rendered as tokens.
+    fn struct_doc(tokens: proc_macro2::TokenStream, name: &str) -> Vec<String> {
+        let file: syn::File = syn::parse2(tokens).unwrap();
+        let s = file
+            .items
+            .into_iter()
+            .find_map(|item| match item {
+                syn::Item::Struct(s) if s.ident == name => Some(s),
+                _ => None,
+            })
+            .unwrap();
+        s.attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .map(|attr| match attr.parse_meta() {
+                Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                })) => doc.value(),
+                _ => attr.tokens.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn emit_tests_generates_compile_fail_doctest() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(emit_tests)).unwrap();
+        let doc = struct_doc(augment_trait(t, &args).unwrap(), "RawCap");
+
+        let start = doc
+            .iter()
+            .position(|line| line == "```compile_fail")
+            .unwrap();
+        let body = quote! {
+            = ::core::concat!("let _cap = ", ::core::module_path!(), "::", "RawCap", "::", "new", "();")
+        };
+        assert_eq!(doc[start + 1], body.to_string());
+        assert_eq!(doc[start + 2], "```");
+    }
+
+    #[test]
+    fn emit_tests_doctest_uses_core_path() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(emit_tests, no_std_core = "crate::core2")).unwrap();
+        let doc = struct_doc(augment_trait(t, &args).unwrap(), "RawCap");
+        let start = doc
+            .iter()
+            .position(|line| line == "```compile_fail")
+            .unwrap();
+        assert!(doc[start + 1].starts_with(&quote!(= crate::core2::concat!).to_string()));
+        assert!(!doc[start + 1].contains(":: core ::"));
+    }
+
+    #[test]
+    fn emit_tests_doctest_only_for_pub_traits() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub(crate) trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(emit_tests)).unwrap();
+        let doc = struct_doc(augment_trait(t, &args).unwrap(), "RawCap");
+        assert!(!doc.iter().any(|line| line.starts_with("```")));
+    }
+
+    #[test]
+    fn emit_tests_doctest_uses_unsafe_ctor_name() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(emit_tests, unsafe_ctor)).unwrap();
+        let doc = struct_doc(augment_trait(t, &args).unwrap(), "RawCap");
+        assert!(doc.iter().any(|line| line.contains("\"new_unchecked\"")));
+    }
+
+    #[test]
+    fn no_doctest_without_emit_tests() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let doc = struct_doc(augment_trait(t, &SuitArgs::default()).unwrap(), "RawCap");
+        assert!(!doc.iter().any(|line| line.starts_with("```")));
+    }
+
+    /// Returns the function pointer types that the `verify_arity` checks in the given code
+    /// coerce each method to, by method name.
+    fn arity_checks(tokens: proc_macro2::TokenStream) -> Vec<(String, String)> {
+        let file: syn::File = syn::parse2(tokens).unwrap();
+        file.items
+            .into_iter()
+            .filter_map(|item| match item {
+                syn::Item::Const(c) => match *c.expr {
+                    syn::Expr::Block(b) => Some(b.block.stmts),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|stmt| match stmt {
+                syn::Stmt::Item(syn::Item::Fn(f)) if f.sig.ident == "check" => Some(f.block.stmts),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|stmt| match stmt {
+                syn::Stmt::Local(syn::Local {
+                    pat: syn::Pat::Type(pat),
+                    init: Some((_, init)),
+                    ..
+                }) => match *init {
+                    syn::Expr::Path(p) => {
+                        let ty = &pat.ty;
+                        let method = p.path.segments.last().unwrap().ident.to_string();
+                        Some((method, quote!(#ty).to_string()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn verify_arity_emits_per_method_checks() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+                unsafe fn seed(seed: u8) -> Self;
+                #[hazmat::skip]
+                fn skipped(&self, a: u8);
+                fn generic<T>(&self, t: T);
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(verify_arity)).unwrap();
+        let checks = arity_checks(augment_trait(t.clone(), &args).unwrap());
+        let expected =
+            |method: &str, ty: proc_macro2::TokenStream| (method.to_string(), ty.to_string());
+        assert_eq!(
+            checks,
+            [
+                expected("raw", quote!(fn(_, _) -> _)),
+                expected("seed", quote!(unsafe fn(_, _) -> _)),
+                expected("skipped", quote!(fn(_, _) -> _)),
+            ]
+        );
+
+        // Ambient capabilities aren't passed as arguments.
+        let args: SuitArgs = syn::parse2(quote!(verify_arity, ambient)).unwrap();
+        let checks = arity_checks(augment_trait(t.clone(), &args).unwrap());
+        assert_eq!(checks[0], expected("raw", quote!(fn(_) -> _)));
+
+        let checks = arity_checks(augment_trait(t, &SuitArgs::default()).unwrap());
+        assert!(checks.is_empty());
+    }
+
+    /// Returns the capability types passed to `assert_send_sync::<_>()` in the given code.
+    fn send_sync_assertions(tokens: proc_macro2::TokenStream) -> Vec<String> {
+        use syn::visit::{self, Visit};
+
+        struct Assertions(Vec<String>);
+
+        impl<'ast> Visit<'ast> for Assertions {
+            fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+                if let syn::Expr::Path(p) = &*call.func {
+                    let last = p.path.segments.last().unwrap();
+                    if last.ident == "assert_send_sync" {
+                        let args = &last.arguments;
+                        self.0.push(quote!(#args).to_string());
+                    }
+                }
+                visit::visit_expr_call(self, call);
+            }
+        }
+
+        let file: syn::File = syn::parse2(tokens).unwrap();
+        let mut visitor = Assertions(vec![]);
+        visitor.visit_file(&file);
+        visitor.0
+    }
+
+    #[test]
+    fn send_sync_assertion_only_for_shared_caps() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let tokens = augment_trait(t.clone(), &SuitArgs::default()).unwrap();
+        assert_eq!(
+            send_sync_assertions(tokens),
+            [quote!(::<RawCap>).to_string()]
+        );
+
+        for args in [
+            quote!(thread_local),
+            quote!(thread_local = "negative_impls"),
+        ] {
+            let args: SuitArgs = syn::parse2(args).unwrap();
+            let tokens = augment_trait(t.clone(), &args).unwrap();
+            assert!(send_sync_assertions(tokens).is_empty());
+        }
+    }
+
+    /// Returns the `new()` constructors of the capability in the given code.
+    fn ctors(tokens: proc_macro2::TokenStream) -> Vec<syn::ImplItemMethod> {
+        let file: syn::File = syn::parse2(tokens).unwrap();
+        file.items
+            .into_iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(i) if i.trait_.is_none() => Some(i.items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                syn::ImplItem::Method(m) if m.sig.ident == "new" => Some(m),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn doc_ctor_is_pub_only_under_cfg_doc() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(doc_ctor)).unwrap();
+        let ctors: Vec<_> = ctors(augment_trait(t, &args).unwrap())
+            .into_iter()
+            .map(|m| {
+                let cfg = m
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path.is_ident("cfg"))
+                    .map(|attr| attr.tokens.to_string());
+                let vis = &m.vis;
+                (cfg, quote!(#vis).to_string())
+            })
+            .collect();
+        assert_eq!(
+            ctors,
+            [
+                (Some("(doc)".into()), "pub".into()),
+                (Some("(not (doc))".into()), quote!(pub(crate)).to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn cap_default_impls_none_has_no_ctor_doc() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let has_doc = |m: &syn::ImplItemMethod| m.attrs.iter().any(|a| a.path.is_ident("doc"));
+
+        let tokens = augment_trait(t.clone(), &SuitArgs::default()).unwrap();
+        assert!(ctors(tokens).iter().all(has_doc));
+
+        for args in [
+            quote!(cap_default_impls = "none"),
+            quote!(cap_default_impls = "none", doc_ctor),
+        ] {
+            let args: SuitArgs = syn::parse2(args).unwrap();
+            let ctors = ctors(augment_trait(t.clone(), &args).unwrap());
+            assert!(!ctors.is_empty());
+            assert!(!ctors.iter().any(has_doc));
+        }
+    }
+
+    /// Compiles the given code as a library with `rustc`, and returns its diagnostics.
+    fn rustc_stderr(name: &str, code: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("hazmat-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let src = dir.join("lib.rs");
+        std::fs::write(&src, code).unwrap();
+        let output =
+            std::process::Command::new(std::env::var_os("RUSTC").unwrap_or("rustc".into()))
+                .args([
+                    "--edition",
+                    "2021",
+                    "--crate-type",
+                    "lib",
+                    "--emit",
+                    "metadata",
+                ])
+                .arg("--out-dir")
+                .arg(&dir)
+                .arg(&src)
+                .output()
+                .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        String::from_utf8(output.stderr).unwrap()
+    }
+
+    #[test]
+    fn safe_ext_blanket_impl_is_not_recommended() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Raw {
+                fn raw(&self) -> u8;
+            }
+        };
+        let args: SuitArgs = syn::parse2(quote!(safe_ext = "RawExt")).unwrap();
+        let tokens = augment_trait(t, &args).unwrap();
+        let code = quote! {
+            #tokens
+
+            fn needs<T: RawExt>(_: T) {}
+
+            pub fn call() {
+                needs(1u8);
+            }
+        }
+        .to_string();
+        let blanket_note = "required for `u8` to implement `RawExt`";
+
+        let stderr = rustc_stderr("do-not-recommend", &code);
+        assert!(stderr.contains("error[E0277]"), "{}", stderr);
+        assert!(!stderr.contains(blanket_note), "{}", stderr);
+
+        // Without the attribute, the error points at the blanket impl.
+        let attr = quote!(#[diagnostic::do_not_recommend]).to_string();
+        assert!(code.contains(&attr));
+        let stderr = rustc_stderr("recommend", &code.replace(&attr, ""));
+        assert!(stderr.contains(blanket_note), "{}", stderr);
+    }
+
+    #[test]
+    fn cap_ty_errors() {
+        let t: syn::ItemTrait = parse_quote! {
+            pub trait Encrypt {
+                fn encrypt(&self, msg: u32) -> u32;
+            }
+        };
+
+        let args: SuitArgs = syn::parse2(quote!(cap_ty = SharedCap, thread_local)).unwrap();
+        let err = augment_trait(t.clone(), &args).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "hazmat::suit argument `thread_local` can't be combined with `cap_ty`"
+        );
+
+        // A misspelled path is used before the trait, so that it is reported first.
+        let args: SuitArgs = syn::parse2(quote!(cap_ty = SharedCapp)).unwrap();
+        let tokens = augment_trait(t, &args).unwrap();
+        let usage = quote! {
+            const _: fn() = || {
+                let _: ::core::marker::PhantomData<SharedCapp>;
+            };
+        };
+        assert!(tokens.to_string().starts_with(&usage.to_string()));
+        let code = quote! {
+            pub struct SharedCap;
+            #tokens
+        };
+        let stderr = rustc_stderr("cap-ty-misspelled", &code.to_string());
+        assert!(
+            stderr.contains("cannot find type `SharedCapp` in this scope"),
+            "{}",
+            stderr
+        );
     }
 }
@@ -0,0 +1,528 @@
+//! Tests that misuses of `hazmat` fail to compile.
+
+/// A by-reference capability can't be added to a method whose elided output lifetimes
+/// would then become ambiguous.
+///
+/// ```compile_fail
+/// use std::borrow::Cow;
+///
+/// #[hazmat::suit(by_ref)]
+/// pub trait Decode {
+///     fn decode(bytes: &[u8]) -> Cow<'_, [u8]>;
+/// }
+/// ```
+pub struct ByRefAmbiguousElision;
+
+/// The same applies to tuples of borrows in receiverless methods.
+///
+/// ```compile_fail
+/// #[hazmat::suit(by_ref)]
+/// pub trait Split {
+///     fn split(bytes: &[u8]) -> (&[u8], &[u8]);
+/// }
+/// ```
+pub struct ByRefAmbiguousTupleElision;
+
+/// An existing capability type that doesn't exist is reported at the attribute.
+///
+/// ```compile_fail,E0425
+/// pub struct SharedCap;
+///
+/// #[hazmat::suit(cap_ty = SharedCapp)]
+/// pub trait Encrypt {
+///     fn encrypt(&self, msg: u32) -> u32;
+/// }
+/// ```
+pub struct CapTyMisspelled;
+
+/// An existing capability type can't be combined with arguments that configure the
+/// generated capability.
+///
+/// ```compile_fail
+/// pub struct SharedCap;
+///
+/// #[hazmat::suit(cap_ty = SharedCap, thread_local)]
+/// pub trait Encrypt {
+///     fn encrypt(&self, msg: u32) -> u32;
+/// }
+/// ```
+pub struct CapTyConflict;
+
+/// An existing capability type reused with `cap_ty` still can't be constructed by other
+/// crates, which can only name it.
+///
+/// ```
+/// use hazmat::__cap_ty_fixture::{Shared, SharedCap};
+///
+/// fn forward<T: Shared>(t: &T, cap: SharedCap) {
+///     t.shared(cap)
+/// }
+/// ```
+///
+/// ```compile_fail,E0423
+/// use hazmat::__cap_ty_fixture::{Shared, SharedCap};
+///
+/// fn forge<T: Shared>(t: &T) {
+///     t.shared(SharedCap)
+/// }
+/// ```
+pub struct CapTyOutsideCrate;
+
+/// The fields of a capability are private, so code outside the module defining the trait
+/// can't destructure the capability to access them.
+///
+/// ```compile_fail,E0603
+/// pub struct SignOp;
+///
+/// mod upstream {
+///     #[hazmat::suit]
+///     pub trait Sign {
+///         #[hazmat::marker(super::SignOp)]
+///         fn sign(&self);
+///     }
+/// }
+///
+/// fn destructure(cap: upstream::SignCap<SignOp>) {
+///     let upstream::SignCap(_inner) = cap;
+/// }
+/// ```
+///
+/// ```compile_fail,E0603
+/// pub struct SignOp;
+///
+/// mod upstream {
+///     #[hazmat::suit]
+///     pub trait Sign {
+///         #[hazmat::marker(super::SignOp)]
+///         fn sign(&self);
+///     }
+/// }
+///
+/// fn destructure(cap: upstream::SignCap<SignOp>) {
+///     let upstream::SignCap(..) = cap;
+/// }
+/// ```
+pub struct CapDestructure;
+
+/// A capability minted via a guard can't be constructed without the guard.
+///
+/// ```compile_fail,E0061
+/// pub struct ProvenanceGuard(());
+///
+/// #[hazmat::suit(mint_via = ProvenanceGuard)]
+/// pub trait Sign {
+///     fn sign(&self, msg: u32) -> u32;
+/// }
+///
+/// fn sign<T: Sign>(t: &T, msg: u32) -> u32 {
+///     t.sign(msg, SignCap::new())
+/// }
+/// ```
+///
+/// Nor can it be constructed directly, outside of the module that defines it.
+///
+/// ```compile_fail,E0603
+/// mod upstream {
+///     pub struct ProvenanceGuard(());
+///
+///     #[hazmat::suit(mint_via = ProvenanceGuard)]
+///     pub trait Sign {
+///         fn sign(&self, msg: u32) -> u32;
+///     }
+/// }
+///
+/// fn sign<T: upstream::Sign>(t: &T, msg: u32) -> u32 {
+///     t.sign(msg, upstream::SignCap(()))
+/// }
+/// ```
+pub struct MintViaWithoutGuard;
+
+/// Outside of the module that defines it, a capability minted from a seed can't be
+/// constructed without a seed, and the seed can't be constructed either.
+///
+/// ```compile_fail,E0603
+/// mod upstream {
+///     pub struct AuthoritySeed(());
+///
+///     #[hazmat::suit(from_seed = AuthoritySeed)]
+///     pub trait Sign {
+///         fn sign(&self, msg: u32) -> u32;
+///     }
+/// }
+///
+/// fn sign<T: upstream::Sign>(t: &T, msg: u32) -> u32 {
+///     t.sign(msg, (&upstream::AuthoritySeed(())).into())
+/// }
+/// ```
+pub struct FromSeedWithoutSeed;
+
+/// A `thread_local` capability is `!Send`, so a future that holds one across an `.await`
+/// is also `!Send`, even if everything else it holds (such as a `Send + Sync` boxed error)
+/// is `Send`.
+///
+/// ```compile_fail
+/// use std::error::Error;
+///
+/// #[hazmat::suit(thread_local)]
+/// pub trait Op {
+///     async fn op(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+/// }
+///
+/// struct Key;
+///
+/// #[hazmat::suit]
+/// impl Op for Key {
+///     async fn op(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+///         Ok(())
+///     }
+/// }
+///
+/// fn assert_send<F: Send>(_: F) {}
+///
+/// fn call(key: &Key) {
+///     assert_send(async move { key.op(OpCap::new()).await });
+/// }
+/// ```
+pub struct ThreadLocalFutureNotSend;
+
+/// The generated code uses the `no_std_core` path instead of `::core`.
+///
+/// ```compile_fail,E0433
+/// mod core2 {}
+///
+/// #[hazmat::suit(no_std_core = "crate::core2")]
+/// pub trait Sign {
+///     #[hazmat::marker(u8)]
+///     fn sign(&self, msg: u32) -> u32;
+/// }
+/// # fn main() {}
+/// ```
+pub struct NoStdCoreMissing;
+
+/// A return-position `impl Trait` captures the lifetime of a `by_ref` capability, so it
+/// can't outlive the capability.
+///
+/// ```compile_fail,E0597
+/// #[hazmat::suit(by_ref)]
+/// pub trait Iter {
+///     fn iter(&self) -> impl Iterator<Item = u8> + '_;
+/// }
+///
+/// fn call<T: Iter>(t: &T) -> impl Iterator<Item = u8> + '_ {
+///     let cap = IterCap::new();
+///     t.iter(&cap)
+/// }
+/// ```
+pub struct ByRefRpitCapture;
+
+/// `cap_size = "zero"` rejects capabilities that carry data.
+///
+/// ```compile_fail,E0080
+/// pub struct AuditedCap(u64);
+///
+/// #[hazmat::suit(cap_size = "zero", cap_ty = AuditedCap)]
+/// pub trait Sign {
+///     fn sign(&self, msg: u32) -> u32;
+/// }
+/// ```
+pub struct CapSizeNotZero;
+
+/// A suited impl that is missing a method is reported by the compiler as usual ("not all
+/// trait items implemented, missing: `verify`"). The macro only augments the methods that
+/// are present, so it doesn't obscure this error. Note that the compiler quotes the trait
+/// method as written in the source, without the capability argument.
+///
+/// ```compile_fail,E0046
+/// #[hazmat::suit]
+/// pub trait Sign {
+///     fn sign(&self, msg: u32) -> u32;
+///     fn verify(&self, sig: u32) -> bool;
+/// }
+///
+/// struct Key;
+///
+/// #[hazmat::suit]
+/// impl Sign for Key {
+///     fn sign(&self, msg: u32) -> u32 {
+///         msg
+///     }
+/// }
+/// ```
+pub struct ImplMissingMethod;
+
+/// A stateful capability used with `cap_ty` can't be forged outside of the module that
+/// defines it.
+///
+/// ```compile_fail,E0451
+/// mod upstream {
+///     pub struct SessionHandle {
+///         id: u32,
+///     }
+///
+///     #[hazmat::suit(cap_ty = SessionHandle, by_ref)]
+///     pub trait Transmit {
+///         fn transmit(&self, msg: u32) -> u32;
+///     }
+/// }
+///
+/// fn transmit<T: upstream::Transmit>(t: &T) -> u32 {
+///     t.transmit(1, &upstream::SessionHandle { id: 7 })
+/// }
+/// ```
+pub struct StatefulCapForged;
+
+/// Macro invocations inside a suited trait are rejected, because the methods they
+/// generate wouldn't require the capability.
+///
+/// ```compile_fail
+/// macro_rules! extra_method {
+///     () => {
+///         fn extra(&self) -> u32;
+///     };
+/// }
+///
+/// #[hazmat::suit]
+/// pub trait Sign {
+///     fn sign(&self, msg: u32) -> u32;
+///
+///     extra_method!();
+/// }
+/// ```
+pub struct TraitMacroInvocation;
+
+/// A `Send`-bounded return-position `impl Trait` can't hold a `thread_local` capability.
+///
+/// ```compile_fail
+/// use core::future::Future;
+///
+/// #[hazmat::suit(thread_local)]
+/// pub trait Work {
+///     fn step(&self) -> u32;
+///
+///     fn work(&self) -> impl Future<Output = u32> + Send
+///     where
+///         Self: Sync,
+///     {
+///         async move {
+///             core::future::ready(()).await;
+///             self.step(cap)
+///         }
+///     }
+/// }
+/// ```
+pub struct ThreadLocalSendRpit;
+
+/// A type that doesn't implement the trait doesn't implement its safe extension trait.
+/// (That the error doesn't mention the blanket implementation is checked by a unit test
+/// in `hazmat-macros`, because doctests can't check the message.)
+///
+/// ```compile_fail,E0277
+/// #[hazmat::suit(safe_ext = "RawExt")]
+/// pub trait Raw {
+///     fn raw(&self) -> u8;
+/// }
+///
+/// fn needs<T: RawExt>(_: T) {}
+///
+/// needs(1u8);
+/// ```
+pub struct SafeExtUnimplemented;
+
+/// The impl helper can't be used with a trait that requires implementations to write the
+/// capability parameter explicitly.
+///
+/// ```compile_fail,E0080
+/// #[hazmat::suit(require_explicit_cap)]
+/// pub trait Raw {
+///     fn raw(&self) -> u8;
+/// }
+///
+/// struct Byte(u8);
+///
+/// #[hazmat::suit]
+/// impl Raw for Byte {
+///     fn raw(&self) -> u8 {
+///         self.0
+///     }
+/// }
+/// ```
+pub struct RequireExplicitCapHelper;
+
+/// The same applies when the impl is annotated with `generic_cap`.
+///
+/// ```compile_fail,E0080
+/// #[hazmat::suit(require_explicit_cap)]
+/// pub trait Raw {
+///     fn raw(&self) -> u8;
+/// }
+///
+/// struct Byte(u8);
+///
+/// #[hazmat::suit(generic_cap)]
+/// impl Raw for Byte {
+///     fn raw(&self) -> u8 {
+///         self.0
+///     }
+/// }
+/// ```
+pub struct RequireExplicitCapGenericHelper;
+
+/// A capability generated with `cap_default_impls = "none"` has no `INSTANCE` constant.
+///
+/// ```compile_fail,E0599
+/// #[hazmat::suit(cap_default_impls = "none")]
+/// pub trait Raw {
+///     fn raw(&self) -> u8;
+/// }
+///
+/// let _cap = RawCap::INSTANCE;
+/// ```
+pub struct CapDefaultImplsNone;
+
+/// A skipped method doesn't take the capability, so its default body can't use it.
+///
+/// ```compile_fail,E0425
+/// #[hazmat::suit]
+/// pub trait Count {
+///     fn count(&self) -> u32;
+///
+///     #[hazmat::skip]
+///     fn count_twice(&self) -> u32 {
+///         self.count(cap) * 2
+///     }
+/// }
+/// ```
+pub struct SkipUsesCap;
+
+/// With `unsafe_ctor`, the capability can't be minted outside of an `unsafe` block.
+///
+/// ```compile_fail,E0133
+/// #[hazmat::suit(unsafe_ctor)]
+/// pub trait Erase {
+///     fn erase(&self);
+/// }
+///
+/// fn erase<T: Erase>(t: &T) {
+///     t.erase(EraseCap::new_unchecked())
+/// }
+/// ```
+pub struct UnsafeCtorWithoutUnsafe;
+
+/// With `cap_in_mod`, the capability must be re-exported from the given module.
+///
+/// ```compile_fail,E0425
+/// pub mod capabilities {}
+///
+/// #[hazmat::suit(cap_in_mod = "capabilities")]
+/// pub trait Hash {
+///     fn hash(&self) -> u32;
+/// }
+/// # fn main() {}
+/// ```
+pub struct CapInModMissingReexport;
+
+/// A default method can't move a `thread_local` capability into another thread.
+///
+/// ```compile_fail,E0277
+/// #[hazmat::suit(thread_local)]
+/// pub trait Op {
+///     fn op(&self) -> u32;
+///
+///     fn spawn_task(&self) -> std::thread::JoinHandle<u32>
+///     where
+///         Self: Clone + Send + 'static,
+///     {
+///         let c = cap;
+///         let this = self.clone();
+///         std::thread::spawn(move || this.op(c))
+///     }
+/// }
+/// ```
+pub struct ThreadLocalMovedIntoThread;
+
+/// A sealed trait can't be implemented for types that weren't listed.
+///
+/// ```compile_fail,E0277
+/// mod upstream {
+///     pub struct Ed25519;
+///
+///     #[hazmat::suit(sealed(Ed25519))]
+///     pub trait Sign {
+///         fn sign(&self, msg: u32) -> u32;
+///     }
+/// }
+///
+/// struct Forged;
+///
+/// #[hazmat::suit]
+/// impl upstream::Sign for Forged {
+///     fn sign(&self, msg: u32) -> u32 {
+///         msg
+///     }
+/// }
+/// ```
+pub struct SealedUnlisted;
+
+/// The supertrait that seals a trait can't be implemented outside of the trait's module.
+///
+/// ```compile_fail,E0603
+/// mod upstream {
+///     pub struct Ed25519;
+///
+///     #[hazmat::suit(sealed(Ed25519))]
+///     pub trait Sign {
+///         fn sign(&self, msg: u32) -> u32;
+///     }
+/// }
+///
+/// struct Forged;
+///
+/// impl upstream::__hazmat_sealed_Sign::Sealed for Forged {}
+/// ```
+pub struct SealedSupertraitPrivate;
+
+/// A return-position `impl Trait` captures the lifetime of a by-reference capability, so
+/// it can't outlive a temporary capability.
+///
+/// ```compile_fail,E0597
+/// #[hazmat::suit(by_ref)]
+/// pub trait ZipRef {
+///     fn zip_ref<'a>(&'a self, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a;
+/// }
+///
+/// fn call<'a, T: ZipRef>(t: &'a T, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+///     let cap = ZipRefCap::new();
+///     t.zip_ref(other, &cap)
+/// }
+/// ```
+pub struct ByRefRpitOutlivesCap;
+
+/// `thread_local` only accepts the `negative_impls` mode.
+///
+/// ```compile_fail
+/// #[hazmat::suit(thread_local = "negative")]
+/// pub trait Local {
+///     fn local(&self) -> u32;
+/// }
+/// ```
+pub struct ThreadLocalUnknownMode;
+
+/// Outside of rustdoc builds, a `doc_ctor` constructor stays crate-internal, so other
+/// crates can name the capability but can't construct it.
+///
+/// ```
+/// fn accept(_cap: hazmat::__doc_ctor_fixture::FixtureCap) {}
+/// ```
+///
+/// ```compile_fail,E0624
+/// let _cap = hazmat::__doc_ctor_fixture::FixtureCap::new();
+/// ```
+pub struct DocCtorOutsideDocs;
+
+/// A capability's `INSTANCE` constant is crate-internal, so other crates can't use it to
+/// obtain the capability either.
+///
+/// ```compile_fail,E0624
+/// let _cap = hazmat::__doc_ctor_fixture::FixtureCap::INSTANCE;
+/// ```
+pub struct InstanceOutsideCrate;
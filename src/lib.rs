@@ -2,4 +2,30 @@
 
 #![deny(rustdoc::broken_intra_doc_links)]
 
-pub use hazmat_macros::suit;
+pub use hazmat_macros::{marker, skip, suit};
+
+#[cfg(doctest)]
+mod compile_fail;
+
+// A capability defined in this crate, for the doctests in `compile_fail` that need to
+// use one from another crate. Doctests link against the normal build of this crate, so
+// this can't be `#[cfg(doctest)]`.
+#[doc(hidden)]
+pub mod __doc_ctor_fixture {
+    #[crate::suit(doc_ctor)]
+    pub trait Fixture {
+        fn fixture(&self);
+    }
+}
+
+// An existing capability type reused with `cap_ty`, for the same reason.
+#[doc(hidden)]
+pub mod __cap_ty_fixture {
+    #[non_exhaustive]
+    pub struct SharedCap;
+
+    #[crate::suit(cap_ty = SharedCap)]
+    pub trait Shared {
+        fn shared(&self);
+    }
+}
@@ -0,0 +1,48 @@
+//! Tests that `#[hazmat::suit]` only processes the item it is applied to.
+
+mod traits {
+    #[hazmat::suit]
+    pub trait Encode {
+        fn encode(&self) -> Header;
+    }
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct Header {
+        pub tag: u8,
+        pub len: u32,
+    }
+
+    pub fn call<T: Encode>(t: &T) -> Header {
+        t.encode(EncodeCap)
+    }
+}
+
+struct Message(Vec<u8>);
+
+#[hazmat::suit]
+impl traits::Encode for Message {
+    fn encode(&self) -> traits::Header {
+        traits::Header {
+            tag: 1,
+            len: self.0.len() as u32,
+        }
+    }
+}
+#[repr(u8)]
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Plain = 7,
+}
+
+#[test]
+fn adjacent_items_are_untouched() {
+    assert_eq!(
+        traits::call(&Message(vec![0; 3])),
+        traits::Header { tag: 1, len: 3 }
+    );
+
+    // The adjacent `#[repr(C)]` struct keeps its layout and fields.
+    assert_eq!(core::mem::size_of::<traits::Header>(), 8);
+    assert_eq!(core::mem::offset_of!(traits::Header, len), 4);
+    assert_eq!(Kind::Plain as u8, 7);
+}
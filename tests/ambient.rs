@@ -0,0 +1,132 @@
+mod traits {
+    #[hazmat::suit(ambient)]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+
+        fn sign_twice(&self, msg: u32) -> u32 {
+            self.sign(self.sign(msg))
+        }
+    }
+
+    pub fn sign<T: Sign>(t: &T, msg: u32) -> u32 {
+        SignCap::with_capability(|| t.sign(msg))
+    }
+
+    pub fn sign_twice<T: Sign>(t: &T, msg: u32) -> u32 {
+        // Scopes can be nested.
+        SignCap::with_capability(|| SignCap::with_capability(|| t.sign_twice(msg)))
+    }
+}
+
+struct Key(u32);
+
+// The impl must also be suited, so that its methods check for the capability.
+#[hazmat::suit(ambient)]
+impl traits::Sign for Key {
+    fn sign(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+}
+
+#[test]
+fn calls_within_scope_succeed() {
+    assert_eq!(traits::sign(&Key(0b0110), 0b0011), 0b0101);
+    assert_eq!(traits::sign_twice(&Key(0b0110), 0b0011), 0b0011);
+    assert!(!traits::SignCap::is_present());
+}
+
+#[test]
+#[should_panic(expected = "`Sign::sign` must be called within `SignCap::with_capability`")]
+fn impl_calls_outside_scope_panic() {
+    use traits::Sign;
+
+    Key(1).sign(2);
+}
+
+#[test]
+#[should_panic(expected = "`Sign::sign_twice` must be called within `SignCap::with_capability`")]
+fn default_calls_outside_scope_panic() {
+    use traits::Sign;
+
+    Key(1).sign_twice(2);
+}
+
+#[test]
+fn scope_is_reset_after_panic() {
+    let res = std::panic::catch_unwind(|| {
+        traits::SignCap::with_capability(|| {
+            assert!(traits::SignCap::is_present());
+            panic!("inside scope");
+        })
+    });
+    assert!(res.is_err());
+    assert!(!traits::SignCap::is_present());
+}
+
+#[test]
+fn scope_is_per_thread() {
+    traits::SignCap::with_capability(|| {
+        let other = std::thread::spawn(traits::SignCap::is_present);
+        assert!(!other.join().unwrap());
+    });
+}
+
+mod sealed {
+    pub struct Hmac;
+
+    #[hazmat::suit(ambient, sealed(Hmac), verify_arity)]
+    pub trait Mac {
+        fn mac(&self, msg: u32) -> u32;
+    }
+
+    #[hazmat::suit(ambient)]
+    impl Mac for Hmac {
+        fn mac(&self, msg: u32) -> u32 {
+            msg.rotate_left(1)
+        }
+    }
+}
+
+#[test]
+fn combines_with_sealed_and_verify_arity() {
+    use sealed::Mac;
+
+    let tag = sealed::MacCap::with_capability(|| sealed::Hmac.mac(0b1001));
+    assert_eq!(tag, 0b10010);
+}
+
+mod asynchronous {
+    #[hazmat::suit(ambient)]
+    pub trait Fetch {
+        async fn fetch(&self) -> u32;
+    }
+}
+
+#[hazmat::suit(ambient)]
+impl asynchronous::Fetch for Key {
+    async fn fetch(&self) -> u32 {
+        self.0
+    }
+}
+
+fn block_on<F: core::future::Future>(f: F) -> F::Output {
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+    match core::pin::pin!(f).poll(&mut cx) {
+        core::task::Poll::Ready(output) => output,
+        core::task::Poll::Pending => unreachable!(),
+    }
+}
+
+#[test]
+fn async_methods_check_when_polled() {
+    use asynchronous::{Fetch, FetchCap};
+
+    // The check runs when the future is first polled, not when it is created.
+    let fut = Key(4).fetch();
+    assert_eq!(FetchCap::with_capability(|| block_on(fut)), 4);
+
+    let key = Key(5);
+    let fut = FetchCap::with_capability(|| key.fetch());
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| block_on(fut)));
+    assert!(res.is_err());
+}
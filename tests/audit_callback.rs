@@ -0,0 +1,60 @@
+use core::panic::Location;
+use std::cell::RefCell;
+
+thread_local! {
+    static MINTED: RefCell<Vec<(&'static str, u32)>> = const { RefCell::new(vec![]) };
+}
+
+fn on_cap_minted(cap: &'static str, location: &'static Location<'static>) {
+    MINTED.with(|m| m.borrow_mut().push((cap, location.line())));
+}
+
+fn minted() -> Vec<(&'static str, u32)> {
+    MINTED.with(|m| m.take())
+}
+
+mod traits {
+    #[hazmat::suit(audit_callback = crate::on_cap_minted, emit_tests)]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn sign_marked(&self, msg: u32) -> u32;
+    }
+
+    pub fn sign<T: Sign>(t: &T, msg: u32) -> (u32, u32) {
+        let cap = SignCap::new();
+        (line!() - 1, t.sign(msg, cap))
+    }
+
+    pub fn sign_marked<T: Sign>(t: &T, msg: u32) -> u32 {
+        t.sign_marked(msg, SignCap::new())
+    }
+}
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl traits::Sign for Key {
+    fn sign(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn sign_marked(&self, msg: u32) -> u32 {
+        msg & self.0
+    }
+}
+
+#[test]
+fn minting_invokes_callback_once() {
+    assert!(minted().is_empty());
+
+    let (line, sig) = traits::sign(&Key(0b0110), 0b0011);
+    assert_eq!(sig, 0b0101);
+    // The callback is told where the capability was minted.
+    assert_eq!(minted(), [("SignCap", line)]);
+
+    assert_eq!(traits::sign_marked(&Key(0b0110), 0b0011), 0b0010);
+    assert_eq!(minted().len(), 1);
+}
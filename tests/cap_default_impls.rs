@@ -0,0 +1,44 @@
+#[macro_use]
+mod common;
+
+mod traits {
+    #[hazmat::suit(cap_default_impls = "none")]
+    pub trait Raw {
+        fn raw(&self) -> u8;
+
+        #[hazmat::marker(u8)]
+        fn raw_marked(&self) -> u8;
+    }
+
+    pub fn call<T: Raw>(t: &T) -> u8 {
+        t.raw(RawCap::new()) + t.raw_marked(RawCap::new())
+    }
+}
+
+struct Byte(u8);
+
+#[hazmat::suit]
+impl traits::Raw for Byte {
+    fn raw(&self) -> u8 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn raw_marked(&self) -> u8 {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn bare_cap_is_usable() {
+    assert_eq!(traits::call(&Byte(3)), 9);
+}
+
+#[test]
+fn bare_cap_implements_no_traits() {
+    assert_not_impl!(traits::RawCap: core::fmt::Debug);
+    assert_not_impl!(traits::RawCap: Clone);
+    assert_not_impl!(traits::RawCap: Default);
+    assert_not_impl!(traits::RawCap: PartialEq);
+    assert_not_impl!(traits::RawCap<u8>: Clone);
+}
@@ -0,0 +1,30 @@
+// The traits in this file only exist for their generated capabilities.
+#![allow(dead_code)]
+
+pub trait Authorization {}
+
+mod markers {
+    pub trait Audited {}
+}
+
+mod traits {
+    #[hazmat::suit(cap_impl(crate::Authorization), cap_impl(crate::markers::Audited))]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+    }
+
+    #[hazmat::suit(cap_impl(crate::Authorization, crate::markers::Audited))]
+    pub trait Verify {
+        #[hazmat::marker(u8)]
+        fn verify(&self, msg: u32, sig: u32) -> bool;
+    }
+}
+
+fn assert_impls<C: Authorization + markers::Audited>() {}
+
+#[test]
+fn cap_implements_listed_traits() {
+    assert_impls::<traits::SignCap>();
+    assert_impls::<traits::VerifyCap>();
+    assert_impls::<traits::VerifyCap<u8>>();
+}
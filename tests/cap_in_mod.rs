@@ -0,0 +1,74 @@
+//! Simulates a crate that collects the capabilities of all of its suited traits in a
+//! single `capabilities` module.
+
+pub mod capabilities {
+    pub use crate::hashing::{HashCap, MacCap};
+    // A capability in a hidden module can be re-exported under its plain name.
+    pub use crate::signing::__hazmat_signing_Sign::SignCap;
+}
+
+pub mod hashing {
+    #[hazmat::suit(cap_in_mod = "capabilities")]
+    pub trait Hash {
+        fn hash(&self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn hash_marked(&self) -> u32;
+    }
+
+    #[hazmat::suit(cap_in_mod = "capabilities", by_ref)]
+    pub trait Mac {
+        fn mac(&self, key: u32) -> u32;
+    }
+}
+
+pub mod signing {
+    #[hazmat::suit(cap_in_mod = "capabilities", cap_module = "signing")]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+    }
+
+    pub fn call<T: Sign>(t: &T, msg: u32) -> u32 {
+        t.sign(msg, crate::capabilities::SignCap::new())
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl hashing::Hash for Num {
+    fn hash(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn hash_marked(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl hashing::Mac for Num {
+    fn mac(&self, key: u32) -> u32 {
+        self.0 ^ key
+    }
+}
+
+#[hazmat::suit(cap_module = "signing")]
+impl signing::Sign for Num {
+    fn sign(&self, msg: u32) -> u32 {
+        self.0 + msg
+    }
+}
+
+#[test]
+fn caps_collected_in_one_module() {
+    use capabilities::{HashCap, MacCap};
+    use hashing::{Hash, Mac};
+
+    let num = Num(3);
+    assert_eq!(num.hash(HashCap::new()), 3);
+    assert_eq!(num.hash_marked(HashCap::new::<u8>()), 6);
+    assert_eq!(num.mac(5, &MacCap::INSTANCE), 6);
+    assert_eq!(signing::call(&num, 4), 7);
+}
@@ -0,0 +1,68 @@
+//! Simulates two crates that each define a `Raw` trait, used together downstream.
+
+mod crate_a {
+    #[hazmat::suit(cap_module = "crate_a")]
+    pub trait Raw {
+        fn raw(&self) -> u8;
+
+        #[hazmat::marker(u8)]
+        fn raw_marked(&self) -> u8;
+    }
+
+    pub fn call<T: Raw>(t: &T) -> u8 {
+        t.raw(__hazmat_crate_a_Raw::RawCap::new())
+            + t.raw_marked(__hazmat_crate_a_Raw::RawCap::new())
+    }
+}
+
+mod crate_b {
+    #[hazmat::suit(cap_module = "crate_b", by_ref)]
+    pub trait Raw {
+        fn raw(&self) -> u8;
+    }
+
+    pub fn call<T: Raw>(t: &T) -> u8 {
+        t.raw(&__hazmat_crate_b_Raw::RawCap::INSTANCE)
+    }
+}
+
+mod downstream {
+    // Both crates export a `Raw` trait, but their capabilities live in distinct modules,
+    // so they can still be named through the glob imports.
+    use super::crate_a::*;
+    use super::crate_b::*;
+
+    pub struct Byte(pub u8);
+
+    #[hazmat::suit(cap_module = "crate_a")]
+    impl super::crate_a::Raw for Byte {
+        fn raw(&self) -> u8 {
+            self.0
+        }
+
+        #[hazmat::marker(u8)]
+        fn raw_marked(&self) -> u8 {
+            self.0 * 2
+        }
+    }
+
+    #[hazmat::suit(cap_module = "crate_b", by_ref)]
+    impl super::crate_b::Raw for Byte {
+        fn raw(&self) -> u8 {
+            self.0 + 1
+        }
+    }
+
+    pub fn signatures() {
+        let _a: fn(&Byte, __hazmat_crate_a_Raw::RawCap) -> u8 = <Byte as super::crate_a::Raw>::raw;
+        let _b: fn(&Byte, &__hazmat_crate_b_Raw::RawCap) -> u8 = <Byte as super::crate_b::Raw>::raw;
+    }
+}
+
+#[test]
+fn same_named_traits_compose() {
+    let byte = downstream::Byte(3);
+    assert_eq!(crate_a::call(&byte), 9);
+    assert_eq!(crate_b::call(&byte), 4);
+    downstream::signatures();
+}
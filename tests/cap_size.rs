@@ -0,0 +1,35 @@
+// The traits in this file only exist for their generated capabilities.
+#![allow(dead_code)]
+
+pub struct Guard(());
+
+#[non_exhaustive]
+pub struct SharedCap;
+
+#[hazmat::suit(cap_size = "zero")]
+pub trait Plain {
+    fn plain(&self);
+}
+
+#[hazmat::suit(cap_size = "zero")]
+pub trait Marked {
+    #[hazmat::marker(u8)]
+    fn marked(&self);
+}
+
+#[hazmat::suit(cap_size = "zero", thread_local, pinned, mint_via = Guard)]
+pub trait Configured {
+    fn configured(&self);
+}
+
+#[hazmat::suit(cap_size = "zero", cap_ty = SharedCap)]
+pub trait Shared {
+    fn shared(&self);
+}
+
+#[test]
+fn zero_sized_caps_compile() {
+    assert_eq!(core::mem::size_of::<PlainCap>(), 0);
+    assert_eq!(core::mem::size_of::<MarkedCap<u8>>(), 0);
+    assert_eq!(core::mem::size_of::<ConfiguredCap>(), 0);
+}
@@ -0,0 +1,138 @@
+mod traits {
+    /// A capability shared between several traits.
+    #[non_exhaustive]
+    pub struct SharedCap;
+
+    #[hazmat::suit(cap_ty = SharedCap)]
+    pub trait Encrypt {
+        fn encrypt(&self, msg: u32) -> u32;
+    }
+
+    #[hazmat::suit(cap_ty = crate::traits::SharedCap, by_ref)]
+    pub trait Decrypt {
+        fn decrypt(&self, ct: u32) -> u32;
+    }
+
+    pub fn roundtrip<T: Encrypt + Decrypt>(t: &T, msg: u32) -> u32 {
+        t.decrypt(t.encrypt(msg, SharedCap), &SharedCap)
+    }
+}
+
+struct Xor(u32);
+
+#[hazmat::suit(cap_ty = traits::SharedCap)]
+impl traits::Encrypt for Xor {
+    fn encrypt(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+}
+
+#[hazmat::suit(cap_ty = traits::SharedCap, by_ref)]
+impl traits::Decrypt for Xor {
+    fn decrypt(&self, ct: u32) -> u32 {
+        ct ^ self.0
+    }
+}
+
+#[test]
+fn shared_cap() {
+    assert_eq!(traits::roundtrip(&Xor(0x55), 42), 42);
+}
+
+mod sessions {
+    /// A stateful capability: holding a `&SessionHandle` proves that a session was
+    /// opened, and the handle carries the session's state.
+    pub struct SessionHandle {
+        id: u32,
+    }
+
+    impl SessionHandle {
+        pub fn id(&self) -> u32 {
+            self.id
+        }
+    }
+
+    pub fn with_session<R>(id: u32, f: impl FnOnce(&SessionHandle) -> R) -> R {
+        f(&SessionHandle { id })
+    }
+
+    #[hazmat::suit(cap_ty = SessionHandle, by_ref)]
+    pub trait Transmit {
+        fn transmit(&self, msg: u32) -> u32;
+    }
+
+    pub fn call<T: Transmit>(t: &T, id: u32, msg: u32) -> u32 {
+        with_session(id, |session| t.transmit(msg, session))
+    }
+}
+
+struct Channel;
+
+#[hazmat::suit(cap_ty = sessions::SessionHandle, by_ref)]
+impl sessions::Transmit for Channel {
+    fn transmit(&self, msg: u32) -> u32 {
+        // The capability is available to the implementation.
+        msg + cap.id()
+    }
+}
+
+#[test]
+fn stateful_cap() {
+    assert_eq!(sessions::call(&Channel, 7, 35), 42);
+}
+
+mod per_implementor {
+    use core::marker::PhantomData;
+
+    /// A capability shared between several traits, but distinct for each implementor.
+    ///
+    /// `Self` isn't `Sized` inside a trait, so neither is `T`.
+    pub struct SharedCap<T: ?Sized>(PhantomData<fn() -> Box<T>>);
+
+    impl<T: ?Sized> SharedCap<T> {
+        pub(crate) fn new() -> Self {
+            SharedCap(PhantomData)
+        }
+    }
+
+    #[hazmat::suit(cap_ty = crate::per_implementor::SharedCap<Self>)]
+    pub trait Sign {
+        fn sign(&self, msg: &[u8]) -> u8;
+    }
+
+    #[hazmat::suit(cap_ty = SharedCap<Self>, by_ref)]
+    pub trait Verify {
+        fn verify(&self, msg: &[u8], sig: u8) -> bool;
+    }
+
+    pub fn roundtrip<T: Sign + Verify>(t: &T, msg: &[u8]) -> bool {
+        t.verify(msg, t.sign(msg, SharedCap::new()), &SharedCap::new())
+    }
+}
+
+struct Ed25519(u8);
+
+#[hazmat::suit(cap_ty = per_implementor::SharedCap<Self>)]
+impl per_implementor::Sign for Ed25519 {
+    fn sign(&self, msg: &[u8]) -> u8 {
+        msg.iter().fold(self.0, |acc, b| acc ^ b)
+    }
+}
+
+// The implementor can also be named explicitly.
+#[hazmat::suit(cap_ty = per_implementor::SharedCap<Ed25519>, by_ref)]
+impl per_implementor::Verify for Ed25519 {
+    fn verify(&self, msg: &[u8], sig: u8) -> bool {
+        use per_implementor::Sign;
+
+        self.sign(msg, per_implementor::SharedCap::new()) == sig
+    }
+}
+
+#[test]
+fn shared_cap_with_self_argument() {
+    assert!(per_implementor::roundtrip(&Ed25519(3), b"msg"));
+
+    let _sign: fn(&Ed25519, &[u8], per_implementor::SharedCap<Ed25519>) -> u8 =
+        <Ed25519 as per_implementor::Sign>::sign;
+}
@@ -0,0 +1,17 @@
+//! Helpers shared by the integration tests.
+
+/// Asserts at compile time that a type does not implement the given trait.
+///
+/// If the type implemented the trait, both impls of `AmbiguousIfImpl` would apply and
+/// the call would fail to infer `A`.
+macro_rules! assert_not_impl {
+    ($ty:ty: $tr:path) => {{
+        trait AmbiguousIfImpl<A> {
+            fn some_item() {}
+        }
+        impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+        impl<T: ?Sized + $tr> AmbiguousIfImpl<u8> for T {}
+
+        <$ty as AmbiguousIfImpl<_>>::some_item()
+    }};
+}
@@ -0,0 +1,196 @@
+//! Tests that default method bodies can use the injected capability.
+
+mod traits {
+    #[hazmat::suit]
+    pub trait Build {
+        fn build(seed: u32) -> Self;
+
+        fn scale(&self) -> u32;
+
+        fn high() -> Self
+        where
+            Self: Sized,
+        {
+            Self::build(u32::MAX, cap)
+        }
+
+        fn rebuilt(&self) -> Self
+        where
+            Self: Sized,
+        {
+            // Capabilities aren't `Copy`, but the trait author can construct more.
+            Self::build(self.scale(BuildCap::new()), cap)
+        }
+    }
+
+    pub fn call<T: Build>() -> (T, T) {
+        let high = T::high(BuildCap::new());
+        let rebuilt = high.rebuilt(BuildCap::new());
+        (high, rebuilt)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Build for Num {
+    fn build(seed: u32) -> Self {
+        Num(seed)
+    }
+
+    fn scale(&self) -> u32 {
+        self.0 / 2
+    }
+}
+
+#[test]
+fn defaults_forward_cap() {
+    assert_eq!(traits::call::<Num>(), (Num(u32::MAX), Num(u32::MAX / 2)));
+}
+
+mod closures {
+    #[hazmat::suit(preset = "standard")]
+    pub trait Sign {
+        fn sign(&self, msg: &[u8]) -> u32;
+
+        // The returned closure captures the `Copy` capability, and passes a copy of it
+        // to each call.
+        fn signer(&self) -> impl Fn(&[u8]) -> u32 + '_ {
+            move |msg| self.sign(msg, cap)
+        }
+    }
+
+    pub fn call<T: Sign>(t: &T, msgs: &[&[u8]]) -> Vec<u32> {
+        let signer = t.signer(SignCap::new());
+        msgs.iter().map(|msg| signer(msg)).collect()
+    }
+}
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl closures::Sign for Key {
+    fn sign(&self, msg: &[u8]) -> u32 {
+        msg.iter().map(|&b| u32::from(b)).sum::<u32>() ^ self.0
+    }
+}
+
+#[test]
+fn defaults_return_cap_capturing_closures() {
+    assert_eq!(closures::call(&Key(1), &[b"a", b"ab"]), [96, 194]);
+}
+
+mod test_only {
+    #[hazmat::suit]
+    pub trait Check {
+        fn value(&self) -> u32;
+
+        #[cfg(test)]
+        fn checked(&self) -> u32 {
+            self.value(cap) + 1
+        }
+
+        // Integration tests are built with `cfg(test)`, so this method (which would fail
+        // to compile) must be removed along with its injected capability.
+        #[cfg(not(test))]
+        fn unchecked(&self) -> u32 {
+            does_not_exist(cap)
+        }
+    }
+
+    pub fn call<T: Check>(t: &T) -> u32 {
+        t.checked(CheckCap::new())
+    }
+}
+
+#[hazmat::suit]
+impl test_only::Check for Key {
+    fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn test_only_defaults_take_cap() {
+    assert_eq!(test_only::call(&Key(1)), 2);
+
+    let _checked: fn(&Key, test_only::CheckCap) -> u32 = <Key as test_only::Check>::checked;
+}
+
+mod threads {
+    #[hazmat::suit]
+    pub trait Op {
+        fn op(&self) -> u32;
+
+        // The default capability is `Copy` and `Send`, so it can be moved into a thread
+        // along with a clone of `self`.
+        fn spawn_task(&self) -> std::thread::JoinHandle<u32>
+        where
+            Self: Clone + Send + 'static,
+        {
+            let c = cap;
+            let this = self.clone();
+            std::thread::spawn(move || this.op(c))
+        }
+    }
+
+    pub fn call<T: Op + Clone + Send + 'static>(t: &T) -> u32 {
+        t.spawn_task(OpCap::new()).join().unwrap()
+    }
+}
+
+#[derive(Clone)]
+struct SharedKey(std::sync::Arc<u32>);
+
+#[hazmat::suit]
+impl threads::Op for SharedKey {
+    fn op(&self) -> u32 {
+        *self.0 + 1
+    }
+}
+
+#[test]
+fn defaults_move_cap_into_threads() {
+    assert_eq!(threads::call(&SharedKey(std::sync::Arc::new(4))), 5);
+}
+
+mod recursion {
+    // The recursive default uses the capability twice, so it must be `Copy`.
+    #[hazmat::suit(preset = "standard")]
+    pub trait Mul {
+        fn mul(&self, other: &Self) -> Self;
+
+        fn pow(&self, n: u32) -> Self
+        where
+            Self: Clone,
+        {
+            if n == 1 {
+                self.clone()
+            } else {
+                self.mul(&self.pow(n - 1, cap), cap)
+            }
+        }
+    }
+
+    pub fn call<T: Mul + Clone>(t: &T, n: u32) -> T {
+        t.pow(n, MulCap::new())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Modular(u64);
+
+#[hazmat::suit]
+impl recursion::Mul for Modular {
+    fn mul(&self, other: &Self) -> Self {
+        Modular(self.0 * other.0 % 1_000_003)
+    }
+}
+
+#[test]
+fn recursive_defaults_reuse_cap() {
+    assert_eq!(recursion::call(&Modular(3), 1), Modular(3));
+    assert_eq!(recursion::call(&Modular(3), 5), Modular(243));
+    assert_eq!(recursion::call(&Modular(10), 7), Modular(999_973));
+}
@@ -0,0 +1,208 @@
+#[macro_use]
+mod common;
+
+fn assert_standard<T: core::fmt::Debug + Clone + Copy>() {}
+
+mod traits {
+    #[hazmat::suit(preset = "standard")]
+    pub trait Standard {
+        fn standard(&self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn standard_marked(&self) -> u32;
+    }
+
+    /// A marker that implements none of the standard traits.
+    pub struct SignOp;
+
+    #[hazmat::suit(preset = "standard")]
+    pub trait Signer {
+        #[hazmat::marker(SignOp)]
+        fn sign(&self, msg: u32) -> u32;
+    }
+
+    pub fn sign_twice<T: Signer>(t: &T, msg: u32) -> u32 {
+        // The capability is `Copy` even though the marker isn't.
+        let cap = SignerCap::new::<SignOp>();
+        t.sign(t.sign(msg, cap), cap)
+    }
+
+    #[hazmat::suit(preset = "minimal")]
+    pub trait Minimal {
+        fn minimal(&self) -> u32;
+    }
+
+    #[hazmat::suit(derive(PartialEq, Eq), derive(Hash))]
+    pub trait Granular {
+        fn granular(&self) -> u32;
+    }
+
+    /// A marker that doesn't implement `PartialEq` itself.
+    pub struct Op;
+
+    #[hazmat::suit(derive(PartialEq, core::cmp::Eq, Hash))]
+    pub trait Compared {
+        fn compared(&self) -> u32;
+
+        #[hazmat::marker(Op)]
+        fn compared_marked(&self) -> u32;
+    }
+
+    // This trait only exists for its generated capability.
+    #[allow(dead_code)]
+    #[hazmat::suit(derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord))]
+    pub trait Ordered {
+        #[hazmat::marker(Op)]
+        fn ordered(&self) -> u32;
+    }
+
+    /// A struct holding capabilities, which can derive `PartialEq` because they do.
+    #[derive(PartialEq, Eq)]
+    pub struct Holder {
+        pub cap: ComparedCap,
+        pub marked: ComparedCap<Op>,
+        pub n: u32,
+    }
+
+    pub fn compare<T: Compared>(t: &T) -> u32 {
+        t.compared(ComparedCap::new()) + t.compared_marked(ComparedCap::new())
+    }
+
+    pub fn holder(n: u32) -> Holder {
+        Holder {
+            cap: ComparedCap::new(),
+            marked: ComparedCap::new(),
+            n,
+        }
+    }
+
+    pub fn call<T: Standard + Minimal + Granular>(t: &T) -> u32 {
+        let cap = StandardCap::new();
+        t.standard(cap)
+            + t.standard(cap)
+            + t.standard_marked(StandardCap::new())
+            + t.minimal(MinimalCap::new())
+            + t.granular(GranularCap::new())
+    }
+
+    pub fn debug() -> String {
+        format!(
+            "{:?} {:?}",
+            StandardCap::new::<()>(),
+            StandardCap::new::<u8>()
+        )
+    }
+
+    pub fn granular_eq() -> bool {
+        GranularCap::new() == GranularCap::new()
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Standard for Num {
+    fn standard(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn standard_marked(&self) -> u32 {
+        self.0
+    }
+}
+
+#[hazmat::suit]
+impl traits::Minimal for Num {
+    fn minimal(&self) -> u32 {
+        self.0
+    }
+}
+
+#[hazmat::suit]
+impl traits::Granular for Num {
+    fn granular(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn standard_preset() {
+    assert_standard::<traits::StandardCap>();
+    assert_standard::<traits::StandardCap<u8>>();
+    assert_not_impl!(traits::StandardCap: Default);
+    assert_eq!(traits::call(&Num(3)), 15);
+    assert!(traits::debug().starts_with("StandardCap("));
+}
+
+#[hazmat::suit]
+impl traits::Signer for Num {
+    #[hazmat::marker(traits::SignOp)]
+    fn sign(&self, msg: u32) -> u32 {
+        msg + self.0
+    }
+}
+
+#[test]
+fn standard_preset_with_bare_marker() {
+    assert_standard::<traits::SignerCap<traits::SignOp>>();
+    assert_eq!(traits::sign_twice(&Num(2), 1), 5);
+    let cap = traits::SignerCap::new::<traits::SignOp>();
+    #[allow(clippy::clone_on_copy)]
+    let _clone = cap.clone();
+    assert!(format!("{:?}", cap).starts_with("SignerCap(PhantomData"));
+}
+
+#[test]
+fn minimal_preset() {
+    assert_not_impl!(traits::MinimalCap: core::fmt::Debug);
+    assert_not_impl!(traits::MinimalCap: Clone);
+    assert_not_impl!(traits::MinimalCap: Copy);
+    assert_not_impl!(traits::MinimalCap: Default);
+}
+
+#[test]
+fn granular_derives() {
+    fn assert_hash<T: core::hash::Hash + Eq>() {}
+    assert_hash::<traits::GranularCap>();
+    assert!(traits::granular_eq());
+    assert_not_impl!(traits::GranularCap: Clone);
+}
+
+#[hazmat::suit]
+impl traits::Compared for Num {
+    fn compared(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(traits::Op)]
+    fn compared_marked(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn structs_holding_caps_compare() {
+    assert_eq!(traits::compare(&Num(2)), 4);
+    assert!(traits::holder(1) == traits::holder(1));
+    assert!(traits::holder(1) != traits::holder(2));
+    assert_not_impl!(traits::ComparedCap: Clone);
+}
+
+#[test]
+fn marked_derives_ignore_the_marker() {
+    use core::{cmp::Ordering, hash::Hash};
+
+    fn assert_derived<T: Clone + Copy + core::fmt::Debug + Hash + Ord>() {}
+    assert_derived::<traits::OrderedCap<traits::Op>>();
+
+    let (a, b) = (
+        traits::OrderedCap::new::<traits::Op>(),
+        traits::OrderedCap::new::<traits::Op>(),
+    );
+    assert_eq!(a.cmp(&b), Ordering::Equal);
+    assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+    let mut set = std::collections::HashSet::new();
+    set.insert(a);
+    assert!(!set.insert(b));
+}
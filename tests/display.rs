@@ -0,0 +1,41 @@
+// The traits in this file only exist for their generated capabilities.
+#![allow(dead_code)]
+
+mod traits {
+    #[hazmat::suit(display = "raw-sign-authorization")]
+    pub trait SignRaw {
+        fn sign_raw(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn sign_raw_marked(&self, msg: u32) -> u32;
+    }
+
+    #[hazmat::suit(display = "pinned-authorization", pinned, preset = "standard")]
+    pub trait Pinned {
+        fn pinned(&self) -> u32;
+    }
+
+    pub fn labels() -> [String; 4] {
+        let cap = PinnedCap::new();
+        [
+            format!("{}", SignRawCap::new::<()>()),
+            format!("{}", SignRawCap::new::<u8>()),
+            format!("{:>24}", SignRawCap::new::<()>()),
+            // Only the label is written, not the capability's marker field.
+            format!("{cap} {cap:?}"),
+        ]
+    }
+}
+
+#[test]
+fn display_writes_label() {
+    assert_eq!(
+        traits::labels(),
+        [
+            "raw-sign-authorization",
+            "raw-sign-authorization",
+            "  raw-sign-authorization",
+            "pinned-authorization PinnedCap(PhantomPinned)",
+        ]
+    );
+}
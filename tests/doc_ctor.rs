@@ -0,0 +1,46 @@
+mod traits {
+    #[hazmat::suit(doc_ctor)]
+    pub trait Hash {
+        fn hash(&self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn hash_marked(&self) -> u32;
+    }
+
+    #[hazmat::suit(doc_ctor, audit_callback = crate::audit)]
+    pub trait Audited {
+        fn audited(&self) -> u32;
+    }
+
+    pub fn call<T: Hash + Audited>(t: &T) -> u32 {
+        t.hash(HashCap::new()) + t.hash_marked(HashCap::new()) + t.audited(AuditedCap::new())
+    }
+}
+
+fn audit(_cap: &str, _location: &'static core::panic::Location<'static>) {}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Hash for Num {
+    fn hash(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn hash_marked(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+#[hazmat::suit]
+impl traits::Audited for Num {
+    fn audited(&self) -> u32 {
+        self.0 * 3
+    }
+}
+
+#[test]
+fn crate_internal_ctor_outside_of_docs() {
+    assert_eq!(traits::call(&Num(1)), 6);
+}
@@ -0,0 +1,26 @@
+// The traits in this file only exist for their generated capabilities.
+#![allow(dead_code)]
+
+// The generated `__hazmat_tests_*` modules are run as part of this test binary.
+mod emitted {
+    #[hazmat::suit(emit_tests)]
+    pub trait Tested {
+        fn tested(&self);
+    }
+
+    #[hazmat::suit(emit_tests, thread_local)]
+    pub trait TestedLocal {
+        fn tested_local(&self);
+    }
+}
+
+mod not_emitted {
+    #[hazmat::suit]
+    pub trait Untested {
+        fn untested(&self);
+    }
+
+    // This would conflict with the generated test module if one were emitted.
+    #[allow(non_snake_case)]
+    mod __hazmat_tests_UntestedCap {}
+}
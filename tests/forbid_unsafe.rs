@@ -0,0 +1,28 @@
+//! Without `unsafe_ctor`, the generated code doesn't use `unsafe`.
+#![forbid(unsafe_code)]
+
+#[hazmat::suit(emit_tests, audit_callback = audit)]
+pub trait Erase {
+    fn erase(&self) -> u32;
+
+    #[hazmat::marker(u8)]
+    fn erase_marked(&self) -> u32 {
+        self.erase(EraseCap::new()) * 2
+    }
+}
+
+fn audit(_cap: &str, _location: &'static core::panic::Location<'static>) {}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl Erase for Num {
+    fn erase(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn safe_ctor() {
+    assert_eq!(Num(3).erase_marked(EraseCap::new()), 6);
+}
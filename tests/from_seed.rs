@@ -0,0 +1,62 @@
+mod authority {
+    /// Established once, when the crate is initialized.
+    pub(crate) struct AuthoritySeed(());
+
+    pub(crate) fn init<R>(f: impl FnOnce(&AuthoritySeed) -> R) -> R {
+        f(&AuthoritySeed(()))
+    }
+}
+
+mod traits {
+    use crate::authority::{self, AuthoritySeed};
+
+    #[hazmat::suit(from_seed = crate::authority::AuthoritySeed, emit_tests)]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn sign_marked(&self, msg: u32) -> u32;
+    }
+
+    pub fn sign<T: Sign>(t: &T, msg: u32) -> u32 {
+        authority::init(|seed: &AuthoritySeed| {
+            t.sign(msg, seed.into()) + t.sign_marked(msg, SignCap::from(seed))
+        })
+    }
+
+    #[hazmat::suit(from_seed = AuthoritySeed, generic_cap)]
+    pub trait Block<const N: usize> {
+        fn len(&self) -> usize;
+    }
+
+    pub fn len<T: Block<4>>(t: &T) -> usize {
+        authority::init(|seed| t.len(BlockCap::new(seed)))
+    }
+}
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl traits::Sign for Key {
+    fn sign(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn sign_marked(&self, msg: u32) -> u32 {
+        msg & self.0
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl traits::Block<4> for Key {
+    fn len(&self) -> usize {
+        4
+    }
+}
+
+#[test]
+fn minting_requires_seed() {
+    assert_eq!(traits::sign(&Key(0b0110), 0b0011), 0b0101 + 0b0010);
+    assert_eq!(traits::len(&Key(0)), 4);
+}
@@ -0,0 +1,237 @@
+mod traits {
+    #[hazmat::suit(generic_cap, safe_ext = "BlockExt")]
+    pub trait Block<const N: usize> {
+        fn xor(&self, other: &Self) -> Self;
+    }
+
+    #[hazmat::suit(generic_cap)]
+    pub trait Convert<T: Copy, U = u8> {
+        fn convert(&self, t: T) -> U;
+    }
+
+    #[hazmat::suit(generic_cap)]
+    pub trait Parse<'a> {
+        fn parse(buf: &'a [u8]) -> Self;
+    }
+
+    pub fn xor<T: Block<N>, const N: usize>(a: &T, b: &T) -> T {
+        a.xor(b, BlockCap::<N>::new())
+    }
+
+    pub fn convert<T: Convert<u32, u64>>(t: &T, x: u32) -> u64 {
+        t.convert(x, ConvertCap::new())
+    }
+
+    pub fn parse<'a, T: Parse<'a>>(buf: &'a [u8]) -> T {
+        T::parse(buf, ParseCap::new())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Bytes<const N: usize>([u8; N]);
+
+#[hazmat::suit(generic_cap)]
+impl traits::Block<16> for Bytes<16> {
+    fn xor(&self, other: &Self) -> Self {
+        Bytes(core::array::from_fn(|i| self.0[i] ^ other.0[i]))
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl traits::Block<8> for Bytes<8> {
+    fn xor(&self, other: &Self) -> Self {
+        Bytes(core::array::from_fn(|i| !(self.0[i] ^ other.0[i])))
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl traits::Convert<u32, u64> for Bytes<8> {
+    fn convert(&self, t: u32) -> u64 {
+        u64::from(t) << 32
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl<'a> traits::Parse<'a> for &'a [u8] {
+    fn parse(buf: &'a [u8]) -> Self {
+        &buf[1..]
+    }
+}
+
+#[test]
+fn const_generic_caps() {
+    assert_eq!(
+        traits::xor(&Bytes([1; 16]), &Bytes([3; 16])),
+        Bytes([2; 16])
+    );
+    assert_eq!(traits::xor(&Bytes([1; 8]), &Bytes([3; 8])), Bytes([!2; 8]));
+
+    // Each impl takes the capability for its own const argument.
+    let _xor: fn(&Bytes<16>, &Bytes<16>, traits::BlockCap<16>) -> Bytes<16> =
+        <Bytes<16> as traits::Block<16>>::xor;
+    let _xor: fn(&Bytes<8>, &Bytes<8>, traits::BlockCap<8>) -> Bytes<8> =
+        <Bytes<8> as traits::Block<8>>::xor;
+}
+
+#[test]
+fn type_and_lifetime_generic_caps() {
+    assert_eq!(traits::convert(&Bytes([0; 8]), 1), 1 << 32);
+    assert_eq!(traits::parse::<&[u8]>(&[1, 2, 3]), &[2, 3]);
+    assert_eq!(core::mem::size_of::<traits::ConvertCap<u32, u64>>(), 0);
+}
+
+#[test]
+fn generic_cap_safe_ext() {
+    use traits::BlockExt;
+
+    assert_eq!(Bytes([1; 16]).xor_safe(&Bytes([3; 16])), Bytes([2; 16]));
+}
+
+mod cap_where_clauses {
+    #[hazmat::suit(generic_cap)]
+    pub trait Encode<F> {
+        // The where-clause refers to the capability that is generated for this trait.
+        fn encode(&self, x: u32) -> u32
+        where
+            EncodeCap<F>: Send;
+
+        fn encode_on_thread(&self, x: u32) -> u32
+        where
+            EncodeCap<F>: Send,
+            Self: Sync,
+        {
+            std::thread::scope(|s| s.spawn(move || self.encode(x, cap)).join().unwrap())
+        }
+    }
+
+    pub fn call<F, T: Encode<F> + Sync>(t: &T, x: u32) -> u32 {
+        t.encode_on_thread(x, EncodeCap::new())
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl cap_where_clauses::Encode<u8> for Bytes<8> {
+    fn encode(&self, x: u32) -> u32
+    where
+        cap_where_clauses::EncodeCap<u8>: Send,
+    {
+        x + u32::from(self.0[0])
+    }
+}
+
+#[test]
+fn where_clauses_mentioning_the_cap() {
+    assert_eq!(cap_where_clauses::call::<u8, _>(&Bytes([1; 8]), 2), 3);
+}
+
+mod default_type_params {
+    #[hazmat::suit(generic_cap)]
+    pub trait Pack<T = u8, const N: usize = 4> {
+        fn pack(&self) -> [T; N];
+    }
+
+    // A default that mentions `Self` isn't kept on the capability.
+    #[hazmat::suit(generic_cap)]
+    pub trait Combine<Rhs = Self> {
+        fn combine(&self, rhs: &Rhs) -> u32;
+    }
+
+    pub fn pack<T: Pack>(t: &T) -> [u8; 4] {
+        t.pack(PackCap::new())
+    }
+
+    pub fn combine<T: Combine>(t: &T) -> u32 {
+        t.combine(t, CombineCap::<T>::new())
+    }
+}
+
+// This impl relies on the trait's defaults, so it takes a `PackCap`, which is
+// `PackCap<u8, 4>`.
+#[hazmat::suit(generic_cap)]
+impl default_type_params::Pack for Bytes<4> {
+    fn pack(&self) -> [u8; 4] {
+        self.0
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl default_type_params::Pack<u16, 2> for Bytes<4> {
+    fn pack(&self) -> [u16; 2] {
+        [
+            u16::from_le_bytes([self.0[0], self.0[1]]),
+            u16::from_le_bytes([self.0[2], self.0[3]]),
+        ]
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl default_type_params::Combine<Bytes<4>> for Bytes<4> {
+    fn combine(&self, rhs: &Bytes<4>) -> u32 {
+        u32::from_le_bytes(self.0) ^ u32::from_le_bytes(rhs.0).rotate_left(8)
+    }
+}
+
+#[test]
+fn impl_relying_on_default_type_params() {
+    assert_eq!(
+        default_type_params::pack(&Bytes([1, 2, 3, 4])),
+        [1, 2, 3, 4]
+    );
+    assert_eq!(
+        <Bytes<4> as default_type_params::Pack<u16, 2>>::pack(
+            &Bytes([1, 0, 2, 0]),
+            default_type_params::PackCap::new(),
+        ),
+        [1, 2]
+    );
+    assert_eq!(
+        default_type_params::combine(&Bytes([0xff, 0, 0, 0])),
+        0xffff
+    );
+
+    let _pack: fn(&Bytes<4>, default_type_params::PackCap<u8, 4>) -> [u8; 4] =
+        <Bytes<4> as default_type_params::Pack>::pack;
+}
+
+mod unsized_params {
+    #[hazmat::suit(generic_cap)]
+    pub trait Hash<T: ?Sized> {
+        fn hash(&self, t: &T) -> u32;
+    }
+
+    #[hazmat::suit(generic_cap)]
+    pub trait Digest<T>
+    where
+        T: ?Sized + AsRef<[u8]>,
+    {
+        fn digest(&self, t: &T) -> u32;
+    }
+
+    pub fn hash<H: Hash<str>>(h: &H, s: &str) -> u32 {
+        h.hash(s, HashCap::<str>::new())
+    }
+
+    pub fn digest<D: Digest<[u8]>>(d: &D, b: &[u8]) -> u32 {
+        d.digest(b, DigestCap::<[u8]>::new())
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl unsized_params::Hash<str> for Bytes<4> {
+    fn hash(&self, t: &str) -> u32 {
+        u32::from_le_bytes(self.0) ^ t.len() as u32
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl unsized_params::Digest<[u8]> for Bytes<4> {
+    fn digest(&self, t: &[u8]) -> u32 {
+        t.iter().chain(&self.0).map(|&b| u32::from(b)).sum()
+    }
+}
+
+#[test]
+fn unsized_type_params() {
+    assert_eq!(unsized_params::hash(&Bytes([1, 0, 0, 0]), "abc"), 2);
+    assert_eq!(unsized_params::digest(&Bytes([1; 4]), &[2, 3]), 9);
+}
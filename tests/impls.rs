@@ -0,0 +1,168 @@
+//! Tests of `#[hazmat::suit]` applied to trait impls.
+
+mod multiple_impls {
+    #[hazmat::suit]
+    pub trait Raw {
+        fn raw(&self) -> u8;
+    }
+
+    pub struct Ed25519;
+    pub struct Ecdsa;
+    pub struct Rsa;
+
+    // These impls are in the same module as the trait, so if the impl-side macro
+    // re-declared `RawCap` they would fail to compile.
+    #[hazmat::suit]
+    impl Raw for Ed25519 {
+        fn raw(&self) -> u8 {
+            1
+        }
+    }
+
+    #[hazmat::suit]
+    impl Raw for Ecdsa {
+        fn raw(&self) -> u8 {
+            2
+        }
+    }
+
+    #[hazmat::suit]
+    impl self::Raw for Rsa {
+        fn raw(&self) -> u8 {
+            3
+        }
+    }
+
+    pub fn call<T: Raw>(t: &T) -> u8 {
+        t.raw(RawCap)
+    }
+}
+
+#[test]
+fn multiple_impls_of_one_trait() {
+    use multiple_impls::*;
+
+    assert_eq!(call(&Ed25519), 1);
+    assert_eq!(call(&Ecdsa), 2);
+    assert_eq!(call(&Rsa), 3);
+}
+
+mod hrtb_where_clauses {
+    #[hazmat::suit]
+    pub trait Raw {
+        fn checksum(&self) -> u8;
+    }
+
+    pub fn call<T: Raw>(t: &T) -> u8 {
+        t.checksum(RawCap)
+    }
+}
+
+struct Bytes<T>(Vec<T>);
+
+impl<'a, T> IntoIterator for &'a Bytes<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[hazmat::suit]
+impl<T> hrtb_where_clauses::Raw for Bytes<T>
+where
+    for<'a> &'a Bytes<T>: IntoIterator<Item = &'a T>,
+    T: Into<u8> + Copy,
+{
+    fn checksum(&self) -> u8 {
+        self.into_iter()
+            .fold(0, |acc, b| acc.wrapping_add((*b).into()))
+    }
+}
+
+#[test]
+fn impl_hrtb_where_clauses() {
+    assert_eq!(hrtb_where_clauses::call(&Bytes(vec![1u8, 2, 250])), 253);
+    assert_eq!(hrtb_where_clauses::call(&Bytes(vec![true, true])), 2);
+}
+
+// Associated type bindings aren't allowed in the trait path of an impl (E0229), so
+// `impl Decode<Output = Foo> for X` can never reach the macro. Generic arguments can, and
+// are kept on the trait path while the capability path drops them.
+mod trait_path_arguments {
+    #[hazmat::suit]
+    pub trait Decode<In> {
+        type Output;
+
+        fn decode(input: In) -> Self::Output;
+    }
+
+    pub fn call<T: Decode<u8, Output = u32>>(input: u8) -> u32 {
+        T::decode(input, DecodeCap)
+    }
+}
+
+struct Widen;
+
+#[hazmat::suit]
+impl trait_path_arguments::Decode<u8> for Widen {
+    type Output = u32;
+
+    fn decode(input: u8) -> Self::Output {
+        input.into()
+    }
+}
+
+#[hazmat::suit]
+impl trait_path_arguments::Decode<u16> for Widen {
+    type Output = u64;
+
+    fn decode(input: u16) -> Self::Output {
+        input.into()
+    }
+}
+
+mod default_type_params {
+    #[hazmat::suit]
+    pub trait Encode<T = u8> {
+        fn encode(&self) -> T;
+    }
+
+    pub fn call<T: Encode>(t: &T) -> u8 {
+        t.encode(EncodeCap)
+    }
+}
+
+// The capability of a non-generic suit doesn't depend on the trait's arguments, so this
+// impl takes an `EncodeCap` whether or not it relies on the default.
+#[hazmat::suit]
+impl default_type_params::Encode for Widen {
+    fn encode(&self) -> u8 {
+        1
+    }
+}
+
+#[hazmat::suit]
+impl default_type_params::Encode<u16> for Widen {
+    fn encode(&self) -> u16 {
+        2
+    }
+}
+
+#[test]
+fn impl_relying_on_default_type_params() {
+    assert_eq!(default_type_params::call(&Widen), 1);
+    assert_eq!(
+        default_type_params::Encode::<u16>::encode(&Widen, default_type_params::EncodeCap),
+        2
+    );
+}
+
+#[test]
+fn impl_trait_path_arguments() {
+    assert_eq!(trait_path_arguments::call::<Widen>(7), 7);
+
+    let _decode: fn(u16, trait_path_arguments::DecodeCap) -> u64 =
+        <Widen as trait_path_arguments::Decode<u16>>::decode;
+}
@@ -0,0 +1,55 @@
+mod traits {
+    #[hazmat::suit]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn sign_marked(&self, msg: u32) -> u32;
+
+        fn sign_twice(&self, msg: u32) -> u32 {
+            self.sign(self.sign(msg, SignCap::INSTANCE), cap)
+        }
+    }
+
+    #[hazmat::suit(generic_cap)]
+    pub trait Block<const N: usize> {
+        fn block(&self) -> [u8; N];
+    }
+
+    // The constant is usable in const contexts.
+    const SIGN_CAP: SignCap = SignCap::INSTANCE;
+    const SIGN_MARKED_CAP: SignCap<u8> = SignCap::INSTANCE;
+
+    pub fn call<T: Sign + Block<4>>(t: &T, msg: u32) -> (u32, [u8; 4]) {
+        (
+            t.sign_twice(msg, SIGN_CAP) + t.sign_marked(msg, SIGN_MARKED_CAP),
+            t.block(BlockCap::<4>::INSTANCE),
+        )
+    }
+}
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl traits::Sign for Key {
+    fn sign(&self, msg: u32) -> u32 {
+        msg + self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn sign_marked(&self, msg: u32) -> u32 {
+        msg * self.0
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl traits::Block<4> for Key {
+    fn block(&self) -> [u8; 4] {
+        self.0.to_le_bytes()
+    }
+}
+
+#[test]
+fn instance_constants() {
+    assert_eq!(traits::call(&Key(2), 3), (7 + 6, [2, 0, 0, 0]));
+}
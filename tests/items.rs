@@ -0,0 +1,67 @@
+//! Tests that non-method trait items pass through `#[hazmat::suit]` unmodified.
+
+mod traits {
+    #[hazmat::suit]
+    pub trait Mixed {
+        const BASE: u32;
+        type Output;
+
+        fn mixed(&self) -> Self::Output;
+    }
+
+    pub fn call<T: Mixed>(t: &T) -> T::Output {
+        t.mixed(MixedCap)
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Mixed for Num {
+    const BASE: u32 = 10;
+    type Output = u32;
+
+    fn mixed(&self) -> Self::Output {
+        Self::BASE + self.0
+    }
+}
+
+#[test]
+fn non_method_items() {
+    assert_eq!(traits::call(&Num(5)), 15);
+}
+
+/// Generates a suited trait with one method per name. Macros can't be invoked inside a
+/// suited trait, but can generate the whole trait.
+macro_rules! primitives {
+    ($trait:ident, $cap:ident, $($method:ident),*) => {
+        mod generated {
+            #[hazmat::suit]
+            pub trait $trait {
+                $(fn $method(&self) -> u32;)*
+            }
+
+            pub fn call<T: $trait>(t: &T) -> u32 {
+                0 $(+ t.$method($cap))*
+            }
+        }
+    };
+}
+
+primitives!(Primitives, PrimitivesCap, first, second);
+
+#[hazmat::suit]
+impl generated::Primitives for Num {
+    fn first(&self) -> u32 {
+        self.0
+    }
+
+    fn second(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn macro_generated_traits() {
+    assert_eq!(generated::call(&Num(5)), 15);
+}
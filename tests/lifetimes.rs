@@ -0,0 +1,671 @@
+//! Tests of how the capability argument interacts with lifetimes in method signatures,
+//! when passed by value and by reference.
+
+use std::borrow::Cow;
+use std::pin::Pin;
+
+mod cow {
+    use std::borrow::Cow;
+
+    #[hazmat::suit]
+    pub trait Encode {
+        fn encode(&self) -> Cow<'_, [u8]>;
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait EncodeRef {
+        fn encode_ref(&self) -> Cow<'_, [u8]>;
+    }
+
+    pub fn call<T: Encode + EncodeRef>(t: &T) -> (Cow<'_, [u8]>, Cow<'_, [u8]>) {
+        (t.encode(EncodeCap), t.encode_ref(&EncodeRefCap))
+    }
+}
+
+struct Bytes(Vec<u8>);
+
+#[hazmat::suit]
+impl cow::Encode for Bytes {
+    fn encode(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.0)
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl cow::EncodeRef for Bytes {
+    fn encode_ref(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.0.iter().rev().cloned().collect())
+    }
+}
+
+#[test]
+fn cow_returns() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    let (a, b) = cow::call(&bytes);
+    assert!(matches!(a, Cow::Borrowed(&[1, 2, 3])));
+    assert_eq!(b, Cow::<[u8]>::Owned(vec![3, 2, 1]));
+}
+
+pub struct RawRef<'a>(&'a [u8]);
+
+mod lifetime_generics {
+    use super::RawRef;
+
+    #[hazmat::suit]
+    pub trait Borrow {
+        fn borrow_raw<'a>(&'a self) -> RawRef<'a>;
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait BorrowRef {
+        fn borrow_raw_ref<'a>(&'a self) -> RawRef<'a>;
+    }
+
+    pub fn call<T: Borrow + BorrowRef>(t: &T) -> (RawRef<'_>, RawRef<'_>) {
+        (t.borrow_raw(BorrowCap), t.borrow_raw_ref(&BorrowRefCap))
+    }
+}
+
+#[hazmat::suit]
+impl lifetime_generics::Borrow for Bytes {
+    fn borrow_raw<'a>(&'a self) -> RawRef<'a> {
+        RawRef(&self.0)
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl lifetime_generics::BorrowRef for Bytes {
+    fn borrow_raw_ref<'a>(&'a self) -> RawRef<'a> {
+        RawRef(&self.0[1..])
+    }
+}
+
+#[test]
+fn lifetime_only_generics() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    let (a, b) = lifetime_generics::call(&bytes);
+    assert_eq!(a.0, &[1, 2, 3]);
+    assert_eq!(b.0, &[2, 3]);
+}
+
+/// Defines a trait covering the common combinations of `&self` / `&mut self` receivers
+/// with elided `&str` / `&[u8]` / `&T` arguments, and an impl of it for `Bytes`.
+macro_rules! elided_matrix {
+    ($module:ident, $name:ident, $cap:expr, ($($args:tt)*)) => {
+        mod $module {
+            #[hazmat::suit($($args)*)]
+            pub trait $name {
+                fn str_ref(&self, s: &str) -> &[u8];
+                fn bytes_ref(&self, b: &[u8]) -> &[u8];
+                fn generic_ref<T: AsRef<[u8]>>(&self, t: &T) -> &[u8];
+                fn str_mut(&mut self, s: &str) -> &mut Vec<u8>;
+                fn bytes_mut(&mut self, b: &[u8]) -> &mut Vec<u8>;
+                fn generic_mut<T: AsRef<[u8]>>(&mut self, t: &T) -> &mut Vec<u8>;
+            }
+
+            pub fn call<T: $name>(t: &mut T) -> Vec<u8> {
+                let mut out = t.str_ref("a", $cap).to_vec();
+                out.extend_from_slice(t.bytes_ref(b"b", $cap));
+                out.extend_from_slice(t.generic_ref(&"c", $cap));
+                t.str_mut("d", $cap);
+                t.bytes_mut(b"e", $cap);
+                out.extend_from_slice(t.generic_mut(&"f", $cap));
+                out
+            }
+        }
+
+        #[hazmat::suit($($args)*)]
+        impl $module::$name for Bytes {
+            fn str_ref(&self, s: &str) -> &[u8] {
+                assert_eq!(s, "a");
+                &self.0
+            }
+            fn bytes_ref(&self, b: &[u8]) -> &[u8] {
+                assert_eq!(b, b"b");
+                &self.0
+            }
+            fn generic_ref<T: AsRef<[u8]>>(&self, t: &T) -> &[u8] {
+                assert_eq!(t.as_ref(), b"c");
+                &self.0
+            }
+            fn str_mut(&mut self, s: &str) -> &mut Vec<u8> {
+                self.0.extend_from_slice(s.as_bytes());
+                &mut self.0
+            }
+            fn bytes_mut(&mut self, b: &[u8]) -> &mut Vec<u8> {
+                self.0.extend_from_slice(b);
+                &mut self.0
+            }
+            fn generic_mut<T: AsRef<[u8]>>(&mut self, t: &T) -> &mut Vec<u8> {
+                self.0.extend_from_slice(t.as_ref());
+                &mut self.0
+            }
+        }
+    };
+}
+
+elided_matrix!(elided_value, Elided, ElidedCap, ());
+elided_matrix!(elided_ref, ElidedRef, &ElidedRefCap, (by_ref));
+
+#[test]
+fn elided_argument_lifetimes() {
+    assert_eq!(
+        elided_value::call(&mut Bytes(vec![0])),
+        b"\x00\x00\x00\x00def"
+    );
+    assert_eq!(
+        elided_ref::call(&mut Bytes(vec![0])),
+        b"\x00\x00\x00\x00def"
+    );
+}
+
+mod rpit_captures {
+    #[hazmat::suit]
+    pub trait Iter {
+        fn iter(&self) -> impl Iterator<Item = u8> + '_;
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait IterRef {
+        fn iter_ref(&self) -> impl Iterator<Item = u8> + '_;
+    }
+
+    pub fn call<T: Iter>(t: &T) -> impl Iterator<Item = u8> + '_ {
+        t.iter(IterCap::new())
+    }
+
+    // In return-position `impl Trait` in traits, all lifetimes in the signature are
+    // captured, including that of a by-reference capability. The iterator therefore
+    // can't outlive the capability.
+    pub fn call_ref<T: IterRef>(t: &T) -> Vec<u8> {
+        let cap = IterRefCap::new();
+        t.iter_ref(&cap).collect()
+    }
+}
+
+#[hazmat::suit]
+impl rpit_captures::Iter for Bytes {
+    fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl rpit_captures::IterRef for Bytes {
+    fn iter_ref(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().rev().copied()
+    }
+}
+
+#[test]
+fn rpit_lifetime_captures() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    assert_eq!(rpit_captures::call(&bytes).collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(rpit_captures::call_ref(&bytes), [3, 2, 1]);
+}
+
+mod interdependent_generics {
+    #[hazmat::suit]
+    pub trait Decode {
+        fn decode<'a, T>(&'a self, buf: &'a [u8]) -> T
+        where
+            T: From<&'a [u8]>;
+    }
+
+    pub fn call<'a, D: Decode, T: From<&'a [u8]>>(d: &'a D, buf: &'a [u8]) -> T {
+        d.decode(buf, DecodeCap)
+    }
+}
+
+#[hazmat::suit]
+impl interdependent_generics::Decode for Bytes {
+    fn decode<'a, T>(&'a self, buf: &'a [u8]) -> T
+    where
+        T: From<&'a [u8]>,
+    {
+        T::from(&buf[self.0.len()..])
+    }
+}
+
+#[test]
+fn interdependent_lifetime_and_type_generics() {
+    let bytes = Bytes(vec![0]);
+    let buf = [1, 2, 3];
+    let decoded: Vec<u8> = interdependent_generics::call(&bytes, &buf);
+    assert_eq!(decoded, [2, 3]);
+    let decoded: &[u8] = interdependent_generics::call(&bytes, &buf);
+    assert_eq!(decoded, [2, 3]);
+}
+
+mod nested_anonymous_lifetimes {
+    use super::RawRef;
+
+    #[hazmat::suit]
+    pub trait Compare {
+        fn compare(&self, other: &RawRef<'_>) -> bool;
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait CompareRef {
+        fn compare_ref(&self, other: &RawRef<'_>) -> bool;
+    }
+
+    pub fn call<T: Compare + CompareRef>(t: &T, other: &RawRef<'_>) -> (bool, bool) {
+        (
+            t.compare(other, CompareCap),
+            t.compare_ref(other, &CompareRefCap),
+        )
+    }
+}
+
+#[hazmat::suit]
+impl nested_anonymous_lifetimes::Compare for Bytes {
+    fn compare(&self, other: &RawRef<'_>) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl nested_anonymous_lifetimes::CompareRef for Bytes {
+    fn compare_ref(&self, other: &RawRef<'_>) -> bool {
+        self.0.starts_with(other.0)
+    }
+}
+
+#[test]
+fn nested_anonymous_argument_lifetimes() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    assert_eq!(
+        nested_anonymous_lifetimes::call(&bytes, &RawRef(&[1, 2, 3])),
+        (true, true)
+    );
+    assert_eq!(
+        nested_anonymous_lifetimes::call(&bytes, &RawRef(&[1, 2])),
+        (false, true)
+    );
+}
+
+mod self_iterators {
+    #[hazmat::suit]
+    pub trait Expand {
+        fn expand(&self, n: usize) -> impl Iterator<Item = Self>
+        where
+            Self: Sized;
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait ExpandRef {
+        fn expand_ref(&self, n: usize) -> impl Iterator<Item = Self>
+        where
+            Self: Sized;
+    }
+
+    pub fn call<T: Expand + ExpandRef>(t: &T, n: usize) -> (Vec<T>, Vec<T>) {
+        // As with other return-position `impl Trait`s, the by-reference iterator can't
+        // outlive the capability.
+        let cap = ExpandRefCap::new();
+        (
+            t.expand(n, ExpandCap::new()).collect(),
+            t.expand_ref(n, &cap).collect(),
+        )
+    }
+}
+
+#[hazmat::suit]
+impl self_iterators::Expand for Bytes {
+    fn expand(&self, n: usize) -> impl Iterator<Item = Self>
+    where
+        Self: Sized,
+    {
+        (0..n).map(|i| Bytes(self.0.iter().map(|b| b + i as u8).collect()))
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl self_iterators::ExpandRef for Bytes {
+    fn expand_ref(&self, n: usize) -> impl Iterator<Item = Self>
+    where
+        Self: Sized,
+    {
+        (0..n).map(|i| Bytes(vec![self.0[0]; i]))
+    }
+}
+
+#[test]
+fn self_yielding_iterators() {
+    let (a, b) = self_iterators::call(&Bytes(vec![1, 2]), 3);
+    assert_eq!(
+        a.into_iter().map(|b| b.0).collect::<Vec<_>>(),
+        [vec![1, 2], vec![2, 3], vec![3, 4]]
+    );
+    assert_eq!(
+        b.into_iter().map(|b| b.0).collect::<Vec<_>>(),
+        [vec![], vec![1], vec![1, 1]]
+    );
+}
+
+mod tuple_borrows {
+    #[hazmat::suit]
+    pub trait Split {
+        fn split(&self, mid: usize) -> (&[u8], &[u8]);
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait SplitRef {
+        // With a `&self` receiver, the elided output lifetimes still refer to `self`.
+        fn split_ref(&self, mid: usize) -> (&[u8], &[u8]);
+    }
+
+    pub fn call<T: Split + SplitRef>(t: &T, mid: usize) -> [(&[u8], &[u8]); 2] {
+        let cap = SplitRefCap::new();
+        [t.split(mid, SplitCap), t.split_ref(mid, &cap)]
+    }
+}
+
+#[hazmat::suit]
+impl tuple_borrows::Split for Bytes {
+    fn split(&self, mid: usize) -> (&[u8], &[u8]) {
+        self.0.split_at(mid)
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl tuple_borrows::SplitRef for Bytes {
+    fn split_ref(&self, mid: usize) -> (&[u8], &[u8]) {
+        let (a, b) = self.0.split_at(mid);
+        (b, a)
+    }
+}
+
+#[test]
+fn tuple_of_borrows_returns() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    let [a, b] = tuple_borrows::call(&bytes, 1);
+    assert_eq!(a, (&[1][..], &[2, 3][..]));
+    assert_eq!(b, (&[2, 3][..], &[1][..]));
+}
+
+mod argument_tied_returns {
+    #[hazmat::suit]
+    pub trait Lookup {
+        fn lookup<'a>(&self, key: &'a [u8]) -> &'a [u8];
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait LookupRef {
+        // The named lifetime ties the return to `key`, not to `self` or the capability.
+        fn lookup_ref<'a>(&self, key: &'a [u8]) -> &'a [u8];
+    }
+
+    pub fn call<'a, T: Lookup + LookupRef>(t: &T, key: &'a [u8]) -> [&'a [u8]; 2] {
+        let by_value = t.lookup(key, LookupCap);
+        // The result outlives the borrow of this temporary capability.
+        let by_ref = {
+            let cap = LookupRefCap::new();
+            t.lookup_ref(key, &cap)
+        };
+        [by_value, by_ref]
+    }
+}
+
+#[hazmat::suit]
+impl argument_tied_returns::Lookup for Bytes {
+    fn lookup<'a>(&self, key: &'a [u8]) -> &'a [u8] {
+        &key[..self.0.len().min(key.len())]
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl argument_tied_returns::LookupRef for Bytes {
+    fn lookup_ref<'a>(&self, key: &'a [u8]) -> &'a [u8] {
+        &key[self.0.len().min(key.len())..]
+    }
+}
+
+#[test]
+fn argument_tied_return_lifetimes() {
+    let key = [1, 2, 3, 4];
+    let [a, b] = {
+        let bytes = Bytes(vec![0; 3]);
+        argument_tied_returns::call(&bytes, &key)
+    };
+    assert_eq!(a, &[1, 2, 3][..]);
+    assert_eq!(b, &[4][..]);
+}
+
+mod static_returns {
+    #[hazmat::suit]
+    pub trait Constant {
+        fn constant(&self) -> &'static [u8];
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait ConstantRef {
+        fn constant_ref(&self) -> &'static [u8];
+    }
+
+    pub fn call<T: Constant + ConstantRef>(t: &T) -> [&'static [u8]; 2] {
+        // Neither result borrows `t` or the capability.
+        let by_ref = {
+            let cap = ConstantRefCap::new();
+            t.constant_ref(&cap)
+        };
+        [t.constant(ConstantCap), by_ref]
+    }
+}
+
+#[hazmat::suit]
+impl static_returns::Constant for Bytes {
+    fn constant(&self) -> &'static [u8] {
+        b"by value"
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl static_returns::ConstantRef for Bytes {
+    fn constant_ref(&self) -> &'static [u8] {
+        b"by ref"
+    }
+}
+
+#[test]
+fn static_reference_returns() {
+    let [a, b] = static_returns::call(&Bytes(vec![]));
+    assert_eq!(a, b"by value");
+    assert_eq!(b, b"by ref");
+}
+
+mod merge_into {
+    #[hazmat::suit]
+    pub trait Merge {
+        fn merge_into(&mut self, other: &Self);
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait MergeRef {
+        // The capability's elided lifetime is independent of `self` and `other`.
+        fn merge_into_ref(&mut self, other: &Self);
+    }
+
+    pub fn call<T: Merge + MergeRef>(t: &mut T, other: &T) {
+        t.merge_into(other, MergeCap);
+        let cap = MergeRefCap::new();
+        t.merge_into_ref(other, &cap);
+    }
+}
+
+#[hazmat::suit]
+impl merge_into::Merge for Bytes {
+    fn merge_into(&mut self, other: &Self) {
+        self.0.extend_from_slice(&other.0);
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl merge_into::MergeRef for Bytes {
+    fn merge_into_ref(&mut self, other: &Self) {
+        self.0.extend(other.0.iter().rev());
+    }
+}
+
+#[test]
+fn mutable_self_with_shared_self() {
+    let mut bytes = Bytes(vec![0]);
+    merge_into::call(&mut bytes, &Bytes(vec![1, 2]));
+    assert_eq!(bytes.0, [0, 1, 2, 2, 1]);
+}
+
+mod dual_borrow_rpit {
+    #[hazmat::suit]
+    pub trait Zip {
+        fn zip<'a>(&'a self, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a;
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait ZipRef {
+        fn zip_ref<'a>(&'a self, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a;
+    }
+
+    pub fn call<'a, T: Zip>(t: &'a T, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        t.zip(other, ZipCap::new())
+    }
+
+    // The iterator captures the elided lifetime of the by-reference capability, so it
+    // can't outlive a temporary capability.
+    pub fn call_ref<T: ZipRef>(t: &T, other: &[u8]) -> Vec<u8> {
+        let cap = ZipRefCap::new();
+        t.zip_ref(other, &cap).collect()
+    }
+
+    // A `'static` capability unifies with `'a`, so the iterator can be returned.
+    pub fn call_ref_static<'a, T: ZipRef>(
+        t: &'a T,
+        other: &'a [u8],
+    ) -> impl Iterator<Item = u8> + 'a {
+        t.zip_ref(other, &ZipRefCap::INSTANCE)
+    }
+}
+
+#[hazmat::suit]
+impl dual_borrow_rpit::Zip for Bytes {
+    fn zip<'a>(&'a self, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        self.0.iter().zip(other).map(|(a, b)| a ^ b)
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl dual_borrow_rpit::ZipRef for Bytes {
+    fn zip_ref<'a>(&'a self, other: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        self.0.iter().zip(other).map(|(a, b)| a + b)
+    }
+}
+
+#[test]
+fn rpit_borrowing_self_and_argument() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    let other = [3, 3, 3];
+    assert_eq!(
+        dual_borrow_rpit::call(&bytes, &other).collect::<Vec<_>>(),
+        [2, 1, 0]
+    );
+    assert_eq!(dual_borrow_rpit::call_ref(&bytes, &other), [4, 5, 6]);
+    assert_eq!(
+        dual_borrow_rpit::call_ref_static(&bytes, &other).collect::<Vec<_>>(),
+        [4, 5, 6]
+    );
+}
+
+mod gat_returns {
+    #[hazmat::suit]
+    pub trait View {
+        type Out<'a>
+        where
+            Self: 'a;
+
+        fn view<'a>(&'a self) -> Self::Out<'a>;
+    }
+
+    pub fn call<T: View>(t: &T) -> T::Out<'_> {
+        t.view(ViewCap::new())
+    }
+}
+
+#[hazmat::suit]
+impl gat_returns::View for Bytes {
+    type Out<'a> = &'a [u8];
+
+    fn view<'a>(&'a self) -> Self::Out<'a> {
+        &self.0[1..]
+    }
+}
+
+#[test]
+fn explicit_gat_returns() {
+    let bytes = Bytes(vec![1, 2, 3]);
+    assert_eq!(gat_returns::call(&bytes), &[2, 3]);
+
+    let _view: for<'a> fn(&'a Bytes, gat_returns::ViewCap) -> &'a [u8] =
+        <Bytes as gat_returns::View>::view;
+}
+
+mod gat_rpit_items {
+    #[hazmat::suit]
+    pub trait Stream {
+        type Chunk<'a>
+        where
+            Self: 'a;
+
+        fn stream<'a>(&'a self) -> impl Iterator<Item = Self::Chunk<'a>>;
+    }
+
+    pub fn call<T: Stream>(t: &T) -> Vec<T::Chunk<'_>> {
+        t.stream(StreamCap::new()).collect()
+    }
+}
+
+#[hazmat::suit]
+impl gat_rpit_items::Stream for Bytes {
+    type Chunk<'a> = &'a [u8];
+
+    fn stream<'a>(&'a self) -> impl Iterator<Item = Self::Chunk<'a>> {
+        self.0.chunks(2)
+    }
+}
+
+#[test]
+fn rpit_yielding_gat_items() {
+    let bytes = Bytes(vec![1, 2, 3, 4, 5]);
+    assert_eq!(
+        gat_rpit_items::call(&bytes),
+        [&[1, 2][..], &[3, 4][..], &[5][..]]
+    );
+}
+
+mod pinned_receivers {
+    use std::pin::Pin;
+
+    #[hazmat::suit(by_ref)]
+    pub trait Project {
+        // As with `&mut self`, the elided output lifetime refers to `self`.
+        fn project(self: Pin<&mut Self>) -> &mut u8;
+    }
+
+    pub fn call<T: Project + Unpin>(mut t: T) -> u8 {
+        let cap = ProjectCap::new();
+        let out = Pin::new(&mut t).project(&cap);
+        *out += 1;
+        *out
+    }
+}
+
+#[hazmat::suit(by_ref)]
+impl pinned_receivers::Project for Bytes {
+    fn project(self: Pin<&mut Self>) -> &mut u8 {
+        &mut self.get_mut().0[0]
+    }
+}
+
+#[test]
+fn pinned_reference_receivers() {
+    assert_eq!(pinned_receivers::call(Bytes(vec![1])), 2);
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "tracing")]
+
+// Stand in for the `tracing` crate, so that the generated spans can be observed.
+extern crate self as tracing;
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+thread_local! {
+    static ENTERED: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+}
+
+pub struct Span(&'static str);
+
+impl Span {
+    pub fn entered(self) -> EnteredSpan {
+        ENTERED.with(|entered| entered.borrow_mut().push(self.0));
+        EnteredSpan(std::marker::PhantomData)
+    }
+}
+
+/// Like `tracing::span::EnteredSpan`, this is `!Send` and exits the span when dropped.
+pub struct EnteredSpan(std::marker::PhantomData<*const ()>);
+
+impl Drop for EnteredSpan {
+    fn drop(&mut self) {}
+}
+
+pub trait Instrument: Sized {
+    fn instrument(self, span: Span) -> Instrumented<Self> {
+        Instrumented(self, span.0)
+    }
+}
+
+impl<F: Future> Instrument for F {}
+
+/// Records its span each time it is polled, like `tracing::instrument::Instrumented`
+/// enters its span.
+pub struct Instrumented<F>(F, &'static str);
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        ENTERED.with(|entered| entered.borrow_mut().push(self.1));
+        // SAFETY: The inner future is never moved out of `self`.
+        unsafe { self.map_unchecked_mut(|s| &mut s.0) }.poll(cx)
+    }
+}
+
+#[macro_export]
+macro_rules! debug_span {
+    ($name:literal) => {
+        $crate::Span($name)
+    };
+}
+
+mod traits {
+    #[hazmat::suit(log_usage)]
+    pub trait Logged {
+        fn primitive(&self) -> u32;
+
+        fn derived(&self) -> u32 {
+            self.primitive(cap) + 1
+        }
+
+        async fn derived_async(&self, input: &str) -> Result<u32, std::num::ParseIntError>
+        where
+            Self: Sync,
+        {
+            std::future::ready(()).await;
+            Ok(input.parse::<u32>()? + self.primitive(cap))
+        }
+    }
+
+    pub async fn call_async(t: &crate::Num, input: &str) -> Result<u32, std::num::ParseIntError> {
+        t.derived_async(input, LoggedCap::new()).await
+    }
+
+    pub fn call<T: Logged>(t: &T) -> u32 {
+        t.derived(LoggedCap::new())
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Logged for Num {
+    fn primitive(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn default_methods_enter_spans() {
+    assert_eq!(traits::call(&Num(1)), 2);
+
+    // Only the default method is logged.
+    ENTERED.with(|entered| assert_eq!(*entered.borrow(), ["Logged::derived"]));
+}
+
+fn assert_send<F: Send>(f: F) -> F {
+    f
+}
+
+/// Polls a future that never waits to completion.
+fn block_on<F: Future>(f: F) -> F::Output {
+    let mut cx = Context::from_waker(std::task::Waker::noop());
+    match std::pin::pin!(f).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!(),
+    }
+}
+
+#[test]
+fn async_default_methods_are_instrumented() {
+    // The span isn't held across the `.await`, so the future is still `Send`.
+    let fut = assert_send(traits::call_async(&Num(1), "2"));
+    assert_eq!(block_on(fut), Ok(3));
+    assert!(block_on(traits::call_async(&Num(1), "x")).is_err());
+
+    ENTERED.with(|entered| {
+        assert_eq!(
+            *entered.borrow(),
+            ["Logged::derived_async", "Logged::derived_async"]
+        )
+    });
+}
@@ -0,0 +1,62 @@
+pub struct SignOp;
+pub struct VerifyOp;
+
+mod traits {
+    use super::{SignOp, VerifyOp};
+
+    #[hazmat::suit]
+    pub trait Signer {
+        fn public_key(&self) -> u32;
+
+        #[hazmat::marker(SignOp)]
+        fn sign(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(VerifyOp)]
+        fn verify(&self, msg: u32, sig: u32) -> bool;
+    }
+
+    pub fn sign_and_verify<S: Signer>(signer: &S, msg: u32) -> (u32, bool) {
+        let sig = signer.sign(msg, SignerCap::new::<SignOp>());
+        (
+            signer.public_key(SignerCap::new()),
+            signer.verify(msg, sig, SignerCap::new()),
+        )
+    }
+}
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl traits::Signer for Key {
+    fn public_key(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(SignOp)]
+    fn sign(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+
+    #[hazmat::marker(VerifyOp)]
+    fn verify(&self, msg: u32, sig: u32) -> bool {
+        sig ^ self.0 == msg
+    }
+}
+
+#[test]
+fn marked_caps() {
+    assert_eq!(traits::sign_and_verify(&Key(5), 12), (5, true));
+}
+
+#[test]
+fn marked_caps_are_distinct_types() {
+    use core::any::TypeId;
+    use traits::SignerCap;
+
+    assert_ne!(
+        TypeId::of::<SignerCap<SignOp>>(),
+        TypeId::of::<SignerCap<VerifyOp>>()
+    );
+    assert_ne!(TypeId::of::<SignerCap<SignOp>>(), TypeId::of::<SignerCap>());
+    assert_eq!(core::mem::size_of::<SignerCap<SignOp>>(), 0);
+}
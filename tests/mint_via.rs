@@ -0,0 +1,45 @@
+mod provenance {
+    /// The single chokepoint at which hazardous authority originates.
+    pub struct ProvenanceGuard(());
+
+    pub fn with_guard<R>(f: impl FnOnce(&ProvenanceGuard) -> R) -> R {
+        f(&ProvenanceGuard(()))
+    }
+}
+
+mod traits {
+    use crate::provenance::{with_guard, ProvenanceGuard};
+
+    #[hazmat::suit(mint_via = ProvenanceGuard, emit_tests)]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn sign_marked(&self, msg: u32) -> u32;
+    }
+
+    pub fn sign<T: Sign>(t: &T, msg: u32) -> u32 {
+        with_guard(|guard| {
+            t.sign(msg, SignCap::new(guard)) + t.sign_marked(msg, SignCap::new(guard))
+        })
+    }
+}
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl traits::Sign for Key {
+    fn sign(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn sign_marked(&self, msg: u32) -> u32 {
+        msg & self.0
+    }
+}
+
+#[test]
+fn minting_requires_guard() {
+    assert_eq!(traits::sign(&Key(0b0110), 0b0011), 0b0101 + 0b0010);
+}
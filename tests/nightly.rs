@@ -0,0 +1,201 @@
+//! Tests of capability injection alongside nightly-only language features.
+//!
+//! These tests are only compiled with `RUSTFLAGS="--cfg hazmat_nightly"` on a nightly
+//! toolchain.
+
+#![cfg(hazmat_nightly)]
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+#![feature(macro_attr)]
+#![feature(negative_impls)]
+
+#[macro_use]
+mod common;
+
+mod assoc_const_arrays {
+    #[hazmat::suit]
+    pub trait Digest {
+        const LEN: usize;
+
+        fn digest(&self) -> [u8; Self::LEN];
+    }
+
+    pub fn call<T: Digest>(t: &T) -> [u8; T::LEN] {
+        t.digest(DigestCap)
+    }
+}
+
+struct Fill(u8);
+
+#[hazmat::suit]
+impl assoc_const_arrays::Digest for Fill {
+    const LEN: usize = 4;
+
+    fn digest(&self) -> [u8; Self::LEN] {
+        [self.0; Self::LEN]
+    }
+}
+
+#[test]
+fn assoc_const_array_returns() {
+    assert_eq!(assoc_const_arrays::call(&Fill(7)), [7; 4]);
+}
+
+pub struct Packed<const BITS: usize>(pub u64);
+
+mod const_expr_arguments {
+    use super::Packed;
+
+    #[hazmat::suit]
+    pub trait Pack {
+        const BITS: usize;
+
+        fn pack(&self) -> Packed<{ Self::BITS }>;
+    }
+
+    pub fn call<T: Pack>(t: &T) -> Packed<{ T::BITS }> {
+        t.pack(PackCap)
+    }
+}
+
+#[hazmat::suit]
+impl const_expr_arguments::Pack for Fill {
+    const BITS: usize = 12;
+
+    fn pack(&self) -> Packed<{ Self::BITS }> {
+        Packed(u64::from(self.0) & ((1 << Self::BITS) - 1))
+    }
+}
+
+#[test]
+fn const_expr_generic_arguments() {
+    let packed: Packed<12> = const_expr_arguments::call(&Fill(7));
+    assert_eq!(packed.0, 7);
+}
+
+/// An attribute macro that adds a default method to a trait.
+macro_rules! add_method {
+    attr() ($(#[$meta:meta])* $vis:vis trait $name:ident { $($items:tt)* }) => {
+        $(#[$meta])*
+        $vis trait $name {
+            $($items)*
+
+            fn generated(&self) -> u32 {
+                1
+            }
+        }
+    };
+}
+
+mod attribute_order {
+    // Attribute macros expand from the top down, so `#[hazmat::suit]` below another
+    // attribute macro sees the methods that it generates.
+    #[add_method]
+    #[hazmat::suit]
+    pub trait SuitLast {
+        fn written(&self) -> u32;
+    }
+
+    // `#[hazmat::suit]` above another attribute macro doesn't see its methods.
+    #[hazmat::suit]
+    #[add_method]
+    pub trait SuitFirst {
+        fn written(&self) -> u32;
+    }
+
+    pub fn call<T: SuitLast + SuitFirst>(t: &T) -> u32 {
+        SuitLast::written(t, SuitLastCap)
+            + SuitLast::generated(t, SuitLastCap)
+            + SuitFirst::written(t, SuitFirstCap)
+    }
+}
+
+#[hazmat::suit]
+impl attribute_order::SuitLast for Fill {
+    fn written(&self) -> u32 {
+        self.0.into()
+    }
+}
+
+#[hazmat::suit]
+impl attribute_order::SuitFirst for Fill {
+    fn written(&self) -> u32 {
+        self.0.into()
+    }
+}
+
+#[test]
+fn attribute_macro_order() {
+    use attribute_order::SuitFirst;
+
+    assert_eq!(attribute_order::call(&Fill(7)), 15);
+
+    // The method generated after `#[hazmat::suit]` ran has no capability argument.
+    assert_eq!(Fill(7).generated(), 1);
+}
+
+mod const_expr_where_clauses {
+    #[hazmat::suit]
+    pub trait Expand {
+        fn expand<const N: usize>(&self) -> [u8; N]
+        where
+            [(); N]: Sized;
+
+        // The const-expr bound requires `generic_const_exprs`.
+        fn expand_twice<const N: usize>(&self) -> [u8; N * 2]
+        where
+            [(); N * 2]: Sized;
+    }
+
+    pub fn call<T: Expand>(t: &T) -> ([u8; 3], [u8; 4]) {
+        (t.expand::<3>(ExpandCap), t.expand_twice::<2>(ExpandCap))
+    }
+}
+
+#[hazmat::suit]
+impl const_expr_where_clauses::Expand for Fill {
+    fn expand<const N: usize>(&self) -> [u8; N]
+    where
+        [(); N]: Sized,
+    {
+        [self.0; N]
+    }
+
+    fn expand_twice<const N: usize>(&self) -> [u8; N * 2]
+    where
+        [(); N * 2]: Sized,
+    {
+        [self.0 + 1; N * 2]
+    }
+}
+
+#[test]
+fn const_expr_where_clauses() {
+    assert_eq!(const_expr_where_clauses::call(&Fill(7)), ([7; 3], [8; 4]));
+}
+
+mod negative_impls {
+    #[hazmat::suit(thread_local = "negative_impls")]
+    pub trait Local {
+        fn local(&self) -> u32;
+    }
+
+    pub fn call<T: Local>(t: &T) -> u32 {
+        t.local(LocalCap::new())
+    }
+}
+
+#[hazmat::suit]
+impl negative_impls::Local for Fill {
+    fn local(&self) -> u32 {
+        self.0.into()
+    }
+}
+
+#[test]
+fn thread_local_negative_impls() {
+    assert_eq!(negative_impls::call(&Fill(7)), 7);
+    assert_not_impl!(negative_impls::LocalCap: Send);
+    assert_not_impl!(negative_impls::LocalCap: Sync);
+    assert_eq!(core::mem::size_of::<negative_impls::LocalCap>(), 0);
+}
@@ -0,0 +1,59 @@
+/// A shim that exposes only the parts of `core` used by the generated code.
+mod core2 {
+    pub use core::{clone, concat, fmt, marker, mem, module_path};
+}
+
+mod traits {
+    #[hazmat::suit(
+        no_std_core = "crate::core2",
+        thread_local,
+        preset = "standard",
+        emit_tests
+    )]
+    pub trait Shimmed {
+        fn shimmed(&self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn shimmed_marked(&self) -> u32;
+    }
+
+    #[non_exhaustive]
+    pub struct SharedCap;
+
+    #[hazmat::suit(no_std_core = "crate::core2", cap_ty = SharedCap)]
+    pub trait ShimmedShared {
+        fn shimmed_shared(&self) -> u32;
+    }
+
+    pub fn call<T: Shimmed + ShimmedShared>(t: &T) -> u32 {
+        t.shimmed(ShimmedCap::new())
+            + t.shimmed_marked(ShimmedCap::new())
+            + t.shimmed_shared(SharedCap)
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Shimmed for Num {
+    fn shimmed(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn shimmed_marked(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+#[hazmat::suit(cap_ty = traits::SharedCap)]
+impl traits::ShimmedShared for Num {
+    fn shimmed_shared(&self) -> u32 {
+        self.0 * 3
+    }
+}
+
+#[test]
+fn generated_code_uses_shim() {
+    assert_eq!(traits::call(&Num(1)), 6);
+}
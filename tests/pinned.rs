@@ -0,0 +1,66 @@
+// Some traits in this file only exist for their generated capabilities.
+#![allow(dead_code)]
+
+#[macro_use]
+mod common;
+
+fn assert_unpin<T: Unpin>() {}
+
+mod traits {
+    #[hazmat::suit(pinned)]
+    pub trait Step {
+        fn step(&mut self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn step_marked(&mut self) -> u32;
+    }
+
+    #[hazmat::suit]
+    pub trait Unpinned {
+        fn unpinned(&self) -> u32;
+    }
+
+    pub fn call<T: Step>(t: &mut T) -> u32 {
+        t.step(StepCap::new()) + t.step_marked(StepCap::new())
+    }
+}
+
+struct Counter(u32);
+
+#[hazmat::suit]
+impl traits::Step for Counter {
+    fn step(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn step_marked(&mut self) -> u32 {
+        self.0 += 10;
+        self.0
+    }
+}
+
+#[hazmat::suit]
+impl traits::Unpinned for Counter {
+    fn unpinned(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn pinned_cap_is_usable() {
+    assert_eq!(traits::call(&mut Counter(0)), 1 + 11);
+}
+
+#[test]
+fn pinned_cap_is_not_unpin() {
+    assert_not_impl!(traits::StepCap: Unpin);
+    assert_not_impl!(traits::StepCap<u8>: Unpin);
+    assert_unpin::<traits::UnpinnedCap>();
+}
+
+#[test]
+fn pinned_cap_is_zero_sized() {
+    assert_eq!(core::mem::size_of::<traits::StepCap>(), 0);
+}
@@ -0,0 +1,182 @@
+//! Tests that the capability is placed correctly after arbitrary receiver types.
+
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+mod last {
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[hazmat::suit]
+    pub trait Shared {
+        fn arc_op(self: &Arc<Self>, x: u32) -> u32;
+        fn rc_op(self: &Rc<Self>, x: u32) -> u32;
+        fn pin_op(self: Pin<Arc<Self>>, x: u32) -> u32;
+    }
+
+    pub fn call<T: Shared + Unpin>(t: T, x: u32) -> u32 {
+        let arc = Arc::new(t);
+        let a = arc.arc_op(x, SharedCap::new());
+        let b = Pin::new(arc).pin_op(x, SharedCap::new());
+        a + b
+    }
+
+    pub fn call_rc<T: Shared>(t: T, x: u32) -> u32 {
+        Rc::new(t).rc_op(x, SharedCap::new())
+    }
+}
+
+mod after_receiver {
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[hazmat::suit(position = "after_receiver", safe_ext = "SharedExt")]
+    pub trait Shared {
+        fn arc_op(self: &Arc<Self>, x: u32) -> u32;
+        fn rc_op(self: &Rc<Self>, x: u32) -> u32;
+        fn pin_op(self: Pin<Arc<Self>>, x: u32) -> u32;
+        fn ref_op(&self, x: u32) -> u32;
+        fn no_receiver(x: u32) -> u32;
+    }
+
+    pub fn call<T: Shared + Unpin>(t: T, x: u32) -> u32 {
+        let arc = Arc::new(t);
+        let a = arc.arc_op(SharedCap::new(), x);
+        let b = arc.ref_op(SharedCap::new(), x);
+        let c = T::no_receiver(SharedCap::new(), x);
+        let d = Pin::new(arc).pin_op(SharedCap::new(), x);
+        a + b + c + d
+    }
+
+    pub fn call_rc<T: Shared>(t: T, x: u32) -> u32 {
+        Rc::new(t).rc_op(SharedCap::new(), x)
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl last::Shared for Num {
+    fn arc_op(self: &Arc<Self>, x: u32) -> u32 {
+        self.0 + x
+    }
+
+    fn rc_op(self: &Rc<Self>, x: u32) -> u32 {
+        self.0 * x
+    }
+
+    fn pin_op(self: Pin<Arc<Self>>, x: u32) -> u32 {
+        self.0 - x
+    }
+}
+
+#[hazmat::suit(position = "after_receiver")]
+impl after_receiver::Shared for Num {
+    fn arc_op(self: &Arc<Self>, x: u32) -> u32 {
+        self.0 + x
+    }
+
+    fn rc_op(self: &Rc<Self>, x: u32) -> u32 {
+        self.0 * x
+    }
+
+    fn pin_op(self: Pin<Arc<Self>>, x: u32) -> u32 {
+        self.0 - x
+    }
+
+    fn ref_op(&self, x: u32) -> u32 {
+        self.0 * 2 + x
+    }
+
+    fn no_receiver(x: u32) -> u32 {
+        x
+    }
+}
+
+#[test]
+fn cap_after_arguments() {
+    assert_eq!(last::call(Num(5), 2), 7 + 3);
+    assert_eq!(last::call_rc(Num(5), 2), 10);
+}
+
+#[test]
+fn cap_after_receiver() {
+    assert_eq!(after_receiver::call(Num(5), 2), 7 + 12 + 2 + 3);
+    assert_eq!(after_receiver::call_rc(Num(5), 2), 10);
+}
+
+#[test]
+fn safe_ext_after_receiver() {
+    use after_receiver::SharedExt;
+
+    let arc = Arc::new(Num(5));
+    assert_eq!(arc.arc_op_safe(2), 7);
+    assert_eq!(arc.ref_op_safe(2), 12);
+    assert_eq!(Num::no_receiver_safe(2), 2);
+    assert_eq!(Rc::new(Num(5)).rc_op_safe(2), 10);
+    assert_eq!(Pin::new(arc).pin_op_safe(2), 3);
+}
+
+// The long form of `self` is spelled out on purpose.
+#[allow(clippy::needless_arbitrary_self_type)]
+mod long_form {
+    #[hazmat::suit]
+    pub trait Consume {
+        fn consume(self: Self, x: u32) -> u32
+        where
+            Self: Sized;
+        fn consume_box(self: Box<Self>, x: u32) -> u32;
+    }
+
+    #[hazmat::suit(position = "after_receiver")]
+    pub trait ConsumeFirst {
+        fn consume_first(self: Self, x: u32) -> u32
+        where
+            Self: Sized;
+        fn consume_box_first(self: Box<Self>, x: u32) -> u32;
+    }
+
+    pub fn call<T: Consume + ConsumeFirst + Clone>(t: T, x: u32) -> [u32; 4] {
+        [
+            t.clone().consume(x, ConsumeCap::new()),
+            Box::new(t.clone()).consume_box(x, ConsumeCap::new()),
+            t.clone().consume_first(ConsumeFirstCap::new(), x),
+            Box::new(t).consume_box_first(ConsumeFirstCap::new(), x),
+        ]
+    }
+}
+
+#[derive(Clone)]
+struct Owned(u32);
+
+#[allow(clippy::needless_arbitrary_self_type)]
+#[hazmat::suit]
+impl long_form::Consume for Owned {
+    fn consume(self: Self, x: u32) -> u32 {
+        self.0 + x
+    }
+
+    fn consume_box(self: Box<Self>, x: u32) -> u32 {
+        self.0 * x
+    }
+}
+
+#[allow(clippy::needless_arbitrary_self_type)]
+#[hazmat::suit(position = "after_receiver")]
+impl long_form::ConsumeFirst for Owned {
+    fn consume_first(self: Self, x: u32) -> u32 {
+        self.0 - x
+    }
+
+    fn consume_box_first(self: Box<Self>, x: u32) -> u32 {
+        self.0 / x
+    }
+}
+
+#[test]
+fn long_form_receivers() {
+    assert_eq!(long_form::call(Owned(6), 2), [8, 12, 4, 3]);
+}
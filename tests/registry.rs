@@ -0,0 +1,74 @@
+// Some traits in this file only exist for their generated capabilities.
+#![allow(dead_code)]
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+fn assert_static<T: 'static>() {}
+
+mod traits {
+    use std::any::TypeId;
+
+    use super::Registry;
+
+    #[hazmat::suit]
+    pub trait Sign {
+        fn sign(&self, msg: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn sign_marked(&self, msg: u32) -> u32;
+    }
+
+    #[hazmat::suit(thread_local)]
+    pub trait Local {
+        fn local(&self) -> u32;
+    }
+
+    pub fn register(registry: &mut Registry) {
+        registry.insert(TypeId::of::<SignCap>(), Box::new(SignCap::new::<()>()));
+        registry.insert(TypeId::of::<SignCap<u8>>(), Box::new(SignCap::new::<u8>()));
+        registry.insert(TypeId::of::<LocalCap>(), Box::new(LocalCap::new()));
+    }
+
+    pub fn sign<T: Sign>(t: &T, registry: &mut Registry, msg: u32) -> Option<u32> {
+        let cap = registry.remove(&TypeId::of::<SignCap>())?.downcast().ok()?;
+        Some(t.sign(msg, *cap))
+    }
+}
+
+type Registry = HashMap<TypeId, Box<dyn Any>>;
+
+struct Key(u32);
+
+#[hazmat::suit]
+impl traits::Sign for Key {
+    fn sign(&self, msg: u32) -> u32 {
+        msg ^ self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn sign_marked(&self, msg: u32) -> u32 {
+        msg & self.0
+    }
+}
+
+#[test]
+fn caps_are_static() {
+    assert_static::<traits::SignCap>();
+    assert_static::<traits::SignCap<u8>>();
+    assert_static::<traits::LocalCap>();
+}
+
+#[test]
+fn caps_can_be_stored_type_erased() {
+    let mut registry = Registry::new();
+    traits::register(&mut registry);
+    assert_eq!(registry.len(), 3);
+
+    assert_eq!(
+        traits::sign(&Key(0b0110), &mut registry, 0b0011),
+        Some(0b0101)
+    );
+    // The capability was consumed.
+    assert_eq!(traits::sign(&Key(0b0110), &mut registry, 0b0011), None);
+}
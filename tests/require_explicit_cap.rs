@@ -0,0 +1,31 @@
+mod traits {
+    #[hazmat::suit(require_explicit_cap)]
+    pub trait Raw {
+        fn raw(&self) -> u8;
+
+        #[hazmat::marker(u8)]
+        fn raw_marked(&self) -> u8;
+    }
+
+    pub fn call<T: Raw>(t: &T) -> u8 {
+        t.raw(RawCap::new()) + t.raw_marked(RawCap::new())
+    }
+}
+
+struct Byte(u8);
+
+// Implementations acknowledge the hazard by writing the capability parameter.
+impl traits::Raw for Byte {
+    fn raw(&self, _cap: traits::RawCap) -> u8 {
+        self.0
+    }
+
+    fn raw_marked(&self, _cap: traits::RawCap<u8>) -> u8 {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn explicit_cap_impls() {
+    assert_eq!(traits::call(&Byte(3)), 9);
+}
@@ -0,0 +1,52 @@
+mod traits {
+    #[hazmat::suit(safe_ext = "AddOnceExt")]
+    pub trait AddOnce {
+        fn add_once(self, other: &Self) -> Self;
+    }
+
+    #[hazmat::suit(safe_ext = "ScaleExt")]
+    pub trait Scale<T: Copy + core::ops::AddAssign> {
+        fn factor(&self) -> T;
+
+        fn scale(&self, (value, _): (T, ()), mut offset: T) -> (T, T, T) {
+            let factor = self.factor(cap);
+            offset += value;
+            (factor, value, offset)
+        }
+
+        fn unit() -> Self
+        where
+            Self: Sized;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::AddOnce for Num {
+    fn add_once(self, other: &Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+#[hazmat::suit]
+impl traits::Scale<u32> for Num {
+    fn factor(&self) -> u32 {
+        self.0
+    }
+
+    fn unit() -> Self {
+        Num(1)
+    }
+}
+
+#[test]
+fn safe_ext_methods() {
+    use traits::{AddOnceExt, ScaleExt};
+
+    assert_eq!(Num(7).add_once_safe(&Num(15)), Num(22));
+    assert_eq!(Num(7).factor_safe(), 7);
+    assert_eq!(Num(7).scale_safe((3, ()), 1), (7, 3, 4));
+    assert_eq!(Num::unit_safe(), Num(1));
+}
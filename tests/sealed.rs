@@ -0,0 +1,45 @@
+mod upstream {
+    pub struct Ed25519;
+    pub struct Truncated<const N: usize>;
+
+    #[hazmat::suit(sealed(Ed25519, Truncated<4>), sealed(super::Rsa))]
+    pub trait Sign: Sync {
+        fn sign(&self, msg: u32) -> u32;
+    }
+
+    #[hazmat::suit]
+    impl Sign for Ed25519 {
+        fn sign(&self, msg: u32) -> u32 {
+            msg + 1
+        }
+    }
+
+    #[hazmat::suit]
+    impl Sign for Truncated<4> {
+        fn sign(&self, msg: u32) -> u32 {
+            msg & 0xf
+        }
+    }
+
+    pub fn call<T: Sign>(t: &T, msg: u32) -> u32 {
+        t.sign(msg, SignCap::new())
+    }
+}
+
+// A listed type can implement the trait from outside of its module. The listed path is
+// resolved relative to the trait.
+struct Rsa;
+
+#[hazmat::suit]
+impl upstream::Sign for Rsa {
+    fn sign(&self, msg: u32) -> u32 {
+        msg * 2
+    }
+}
+
+#[test]
+fn listed_types_can_implement() {
+    assert_eq!(upstream::call(&upstream::Ed25519, 0x41), 0x42);
+    assert_eq!(upstream::call(&upstream::Truncated::<4>, 0x41), 0x1);
+    assert_eq!(upstream::call(&Rsa, 0x41), 0x82);
+}
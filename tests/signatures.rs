@@ -0,0 +1,840 @@
+//! Tests that capability injection is unaffected by the shapes of method signatures.
+
+mod fn_pointers {
+    #[hazmat::suit]
+    pub trait Register {
+        fn register(&self, cb: fn(u8) -> u8) -> u8;
+        fn register_c(&self, cb: extern "C" fn(u8) -> u8) -> u8;
+    }
+
+    pub fn call<T: Register>(t: &T, cb: fn(u8) -> u8, cb_c: extern "C" fn(u8) -> u8) -> u8 {
+        t.register(cb, RegisterCap) + t.register_c(cb_c, RegisterCap)
+    }
+}
+
+struct Registry(u8);
+
+#[hazmat::suit]
+impl fn_pointers::Register for Registry {
+    fn register(&self, cb: fn(u8) -> u8) -> u8 {
+        cb(self.0)
+    }
+
+    fn register_c(&self, cb: extern "C" fn(u8) -> u8) -> u8 {
+        cb(self.0)
+    }
+}
+
+fn double(x: u8) -> u8 {
+    x * 2
+}
+
+extern "C" fn triple(x: u8) -> u8 {
+    x * 3
+}
+
+#[test]
+fn fn_pointer_arguments() {
+    assert_eq!(fn_pointers::call(&Registry(2), double, triple), 10);
+}
+
+mod assoc_where_clauses {
+    #[hazmat::suit]
+    pub trait Op {
+        type Output;
+
+        fn op(&self) -> Self::Output
+        where
+            Self::Output: Clone;
+    }
+
+    pub fn call<T: Op>(t: &T) -> T::Output
+    where
+        T::Output: Clone,
+    {
+        t.op(OpCap)
+    }
+}
+
+struct Doubler(u32);
+
+#[hazmat::suit]
+impl assoc_where_clauses::Op for Doubler {
+    type Output = u32;
+
+    fn op(&self) -> Self::Output
+    where
+        Self::Output: Clone,
+    {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn assoc_type_where_clauses() {
+    assert_eq!(assoc_where_clauses::call(&Doubler(4)), 8);
+}
+
+mod optional_self {
+    #[hazmat::suit]
+    pub trait Merge {
+        fn merge(&self, other: Option<&Self>) -> Option<Self>
+        where
+            Self: Sized;
+    }
+
+    pub fn call<T: Merge>(a: &T, b: Option<&T>) -> Option<T> {
+        a.merge(b, MergeCap)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Sum(u32);
+
+#[hazmat::suit]
+impl optional_self::Merge for Sum {
+    fn merge(&self, other: Option<&Self>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        other.map(|o| Sum(self.0 + o.0))
+    }
+}
+
+#[test]
+fn optional_self_arguments() {
+    assert_eq!(optional_self::call(&Sum(1), Some(&Sum(2))), Some(Sum(3)));
+    assert_eq!(optional_self::call(&Sum(1), None), None);
+}
+
+mod trait_object_sinks {
+    #[hazmat::suit]
+    pub trait Digest {
+        fn write_digest(&self, out: &mut dyn core::fmt::Write) -> core::fmt::Result;
+    }
+
+    // The trait remains dyn-compatible.
+    pub fn call(d: &dyn Digest, out: &mut dyn core::fmt::Write) -> core::fmt::Result {
+        d.write_digest(out, DigestCap)
+    }
+}
+
+struct Hex(u8);
+
+#[hazmat::suit]
+impl trait_object_sinks::Digest for Hex {
+    fn write_digest(&self, out: &mut dyn core::fmt::Write) -> core::fmt::Result {
+        write!(out, "{:02x}", self.0)
+    }
+}
+
+#[test]
+fn trait_object_sink_arguments() {
+    let mut out = String::new();
+    trait_object_sinks::call(&Hex(0xab), &mut out).unwrap();
+    assert_eq!(out, "ab");
+}
+
+mod boxed_errors {
+    use std::error::Error;
+
+    #[hazmat::suit]
+    pub trait Op {
+        fn op(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+        async fn op_async(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+    }
+
+    pub fn call<T: Op>(t: &T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        t.op(OpCap)
+    }
+
+    // The default capability is `Send`, so it doesn't prevent the future from crossing
+    // threads (in contrast to `thread_local` capabilities). This needs a concrete type,
+    // because the futures returned by generic `async fn`s in traits aren't known to be
+    // `Send`.
+    pub async fn call_async(t: &crate::Fallible) -> Result<(), Box<dyn Error + Send + Sync>> {
+        t.op_async(OpCap).await?;
+        t.op_async(OpCap).await
+    }
+}
+
+struct Fallible(bool);
+
+#[hazmat::suit]
+impl boxed_errors::Op for Fallible {
+    fn op(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.0 {
+            Ok(())
+        } else {
+            Err("failed".into())
+        }
+    }
+
+    async fn op_async(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.op(boxed_errors::OpCap::new())
+    }
+}
+
+fn assert_send<F: Send>(f: F) -> F {
+    f
+}
+
+/// Polls a future that never waits to completion.
+fn block_on<F: core::future::Future>(f: F) -> F::Output {
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+    match core::pin::pin!(f).poll(&mut cx) {
+        core::task::Poll::Ready(output) => output,
+        core::task::Poll::Pending => unreachable!(),
+    }
+}
+
+#[test]
+fn boxed_error_returns() {
+    assert!(boxed_errors::call(&Fallible(true)).is_ok());
+    assert_eq!(
+        boxed_errors::call(&Fallible(false))
+            .unwrap_err()
+            .to_string(),
+        "failed"
+    );
+    assert!(block_on(assert_send(boxed_errors::call_async(&Fallible(true)))).is_ok());
+    assert!(block_on(assert_send(boxed_errors::call_async(&Fallible(false)))).is_err());
+}
+
+mod turbofish {
+    #[hazmat::suit]
+    pub trait Cast {
+        fn cast<T: From<u8>>() -> T;
+    }
+
+    pub fn call<C: Cast>() -> (u32, u64) {
+        (
+            C::cast::<u32>(CastCap::new()),
+            C::cast::<u64>(CastCap::new()),
+        )
+    }
+}
+
+struct Seven;
+
+#[hazmat::suit]
+impl turbofish::Cast for Seven {
+    fn cast<T: From<u8>>() -> T {
+        T::from(7)
+    }
+}
+
+#[test]
+fn turbofish_generic_methods() {
+    assert_eq!(turbofish::call::<Seven>(), (7, 7));
+
+    // The method's type parameter stays before the capability argument.
+    let _cast: fn(turbofish::CastCap) -> u16 = <Seven as turbofish::Cast>::cast::<u16>;
+}
+
+mod fallible_constructors {
+    #[hazmat::suit]
+    pub trait FromBytes {
+        type Error;
+
+        fn from_bytes(b: &[u8]) -> Result<Self, Self::Error>
+        where
+            Self: Sized;
+    }
+
+    pub fn call<T: FromBytes>(b: &[u8]) -> Result<T, T::Error> {
+        T::from_bytes(b, FromBytesCap)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Point([u8; 2]);
+
+#[hazmat::suit]
+impl fallible_constructors::FromBytes for Point {
+    type Error = usize;
+
+    fn from_bytes(b: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        b.try_into().map(Point).map_err(|_| b.len())
+    }
+}
+
+#[test]
+fn fallible_constructor_returns() {
+    assert_eq!(fallible_constructors::call(&[1, 2]), Ok(Point([1, 2])));
+    assert_eq!(fallible_constructors::call::<Point>(&[1, 2, 3]), Err(3));
+
+    // The capability is appended after the byte slice.
+    let _from_bytes: fn(&[u8], fallible_constructors::FromBytesCap) -> Result<Point, usize> =
+        <Point as fallible_constructors::FromBytes>::from_bytes;
+}
+
+mod layered_caps {
+    #[hazmat::suit]
+    pub trait Lower {
+        fn lower(&self, x: u32) -> u32;
+    }
+
+    #[hazmat::suit]
+    pub trait Upper: Lower {
+        // The upper layer forwards a capability for the lower layer.
+        fn upper(&self, lower: super::layered_caps::LowerCap, x: u32) -> u32;
+    }
+
+    pub fn call<T: Upper>(t: &T, x: u32) -> u32 {
+        t.upper(LowerCap::new(), x, UpperCap::new())
+    }
+}
+
+struct Layered(u32);
+
+#[hazmat::suit]
+impl layered_caps::Lower for Layered {
+    fn lower(&self, x: u32) -> u32 {
+        self.0 + x
+    }
+}
+
+#[hazmat::suit]
+impl layered_caps::Upper for Layered {
+    fn upper(&self, lower: layered_caps::LowerCap, x: u32) -> u32 {
+        use layered_caps::Lower;
+
+        self.lower(x, lower) * 2
+    }
+}
+
+#[test]
+fn caps_of_other_traits_as_arguments() {
+    assert_eq!(layered_caps::call(&Layered(1), 2), 6);
+}
+
+mod send_futures {
+    use core::future::Future;
+
+    #[hazmat::suit]
+    pub trait Work {
+        fn step(&self) -> u32;
+
+        // The default body holds the capability across an `.await`, which is allowed
+        // because the default capability is `Send`.
+        fn work(&self) -> impl Future<Output = u32> + Send
+        where
+            Self: Sync,
+        {
+            async move {
+                core::future::ready(()).await;
+                self.step(cap)
+            }
+        }
+    }
+
+    pub async fn call<T: Work + Sync>(t: &T) -> u32 {
+        t.work(WorkCap::new()).await
+    }
+}
+
+#[hazmat::suit]
+impl send_futures::Work for Fallible {
+    fn step(&self) -> u32 {
+        self.0.into()
+    }
+}
+
+#[test]
+fn send_bounded_rpit_futures() {
+    assert_eq!(
+        block_on(assert_send(send_futures::call(&Fallible(true)))),
+        1
+    );
+}
+
+mod unsized_impl_args {
+    #[hazmat::suit]
+    pub trait Hash {
+        fn hash(&self, data: &(impl AsRef<[u8]> + ?Sized)) -> u32;
+    }
+
+    pub fn call<T: Hash>(t: &T) -> u32 {
+        t.hash("ab", HashCap::new()) + t.hash(&[1u8, 2][..], HashCap::new())
+    }
+}
+
+struct Summer;
+
+#[hazmat::suit]
+impl unsized_impl_args::Hash for Summer {
+    fn hash(&self, data: &(impl AsRef<[u8]> + ?Sized)) -> u32 {
+        data.as_ref().iter().map(|&b| u32::from(b)).sum()
+    }
+}
+
+#[test]
+fn unsized_impl_trait_arguments() {
+    assert_eq!(unsized_impl_args::call(&Summer), 97 + 98 + 1 + 2);
+}
+
+mod const_generic_arrays {
+    #[hazmat::suit]
+    pub trait Sponge {
+        fn absorb<const N: usize>(&mut self, block: &[u8; N]);
+    }
+
+    pub fn call<T: Sponge>(t: &mut T) {
+        t.absorb::<4>(&[1; 4], SpongeCap::new());
+        t.absorb(&[2; 64], SpongeCap::new());
+    }
+}
+
+struct Absorbed(u32);
+
+#[hazmat::suit]
+impl const_generic_arrays::Sponge for Absorbed {
+    fn absorb<const N: usize>(&mut self, block: &[u8; N]) {
+        self.0 += block.iter().map(|&b| u32::from(b)).sum::<u32>();
+    }
+}
+
+#[test]
+fn const_generic_array_arguments() {
+    let mut sponge = Absorbed(0);
+    const_generic_arrays::call(&mut sponge);
+    assert_eq!(sponge.0, 4 + 128);
+
+    // The const generic stays on the method, before the capability argument.
+    let _absorb: fn(&mut Absorbed, &[u8; 64], const_generic_arrays::SpongeCap) =
+        <Absorbed as const_generic_arrays::Sponge>::absorb::<64>;
+}
+
+mod fallible_iterators {
+    #[hazmat::suit]
+    pub trait Stream {
+        fn stream(&self) -> Result<impl Iterator<Item = u8>, String>;
+    }
+
+    pub fn call<T: Stream>(t: &T) -> Result<Vec<u8>, String> {
+        t.stream(StreamCap::new()).map(Iterator::collect)
+    }
+}
+
+struct Counter(u8);
+
+#[hazmat::suit]
+impl fallible_iterators::Stream for Counter {
+    fn stream(&self) -> Result<impl Iterator<Item = u8>, String> {
+        match self.0 {
+            0 => Err("empty".into()),
+            n => Ok(0..n),
+        }
+    }
+}
+
+#[test]
+fn fallible_rpit_returns() {
+    assert_eq!(fallible_iterators::call(&Counter(3)), Ok(vec![0, 1, 2]));
+    assert_eq!(
+        fallible_iterators::call(&Counter(0)),
+        Err("empty".to_string())
+    );
+}
+
+mod phantom_args {
+    use core::marker::PhantomData;
+
+    #[hazmat::suit]
+    pub trait Tagged {
+        fn tagged<T>(&self, _marker: PhantomData<T>) -> usize;
+    }
+
+    pub fn call<X: Tagged>(x: &X) -> (usize, usize) {
+        (
+            x.tagged(PhantomData::<u16>, TaggedCap::new()),
+            x.tagged::<u64>(PhantomData, TaggedCap::new()),
+        )
+    }
+}
+
+struct SizeOf;
+
+#[hazmat::suit]
+impl phantom_args::Tagged for SizeOf {
+    fn tagged<T>(&self, _marker: core::marker::PhantomData<T>) -> usize {
+        core::mem::size_of::<T>()
+    }
+}
+
+#[test]
+fn phantom_data_arguments() {
+    assert_eq!(phantom_args::call(&SizeOf), (2, 8));
+
+    let _tagged: fn(&SizeOf, core::marker::PhantomData<u8>, phantom_args::TaggedCap) -> usize =
+        <SizeOf as phantom_args::Tagged>::tagged::<u8>;
+}
+
+mod async_closures {
+    #[hazmat::suit(preset = "standard")]
+    pub trait Sign {
+        fn sign(&self, msg: &[u8]) -> u32;
+
+        // Async closures are stable (since Rust 1.85), so this needs no nightly features.
+        // Like other closures that are called more than once, it needs a `Copy` capability.
+        fn signer(&self) -> impl AsyncFn(&[u8]) -> u32 + '_ {
+            async move |msg: &[u8]| self.sign(msg, cap)
+        }
+    }
+
+    pub async fn call<T: Sign>(t: &T) -> [u32; 2] {
+        let signer = t.signer(SignCap::new());
+        [signer(b"a").await, signer(b"ab").await]
+    }
+}
+
+#[hazmat::suit]
+impl async_closures::Sign for Summer {
+    fn sign(&self, msg: &[u8]) -> u32 {
+        msg.iter().map(|&b| u32::from(b)).sum()
+    }
+}
+
+#[test]
+fn async_closure_returns() {
+    assert_eq!(block_on(async_closures::call(&Summer)), [97, 195]);
+}
+
+mod boxed_handlers {
+    #[hazmat::suit]
+    pub trait Install {
+        fn install(&mut self, handler: Box<dyn Fn() -> u32>);
+
+        fn fire(&self) -> u32;
+    }
+
+    pub fn call<T: Install>(t: &mut T, x: u32) -> u32 {
+        t.install(Box::new(move || x * 2), InstallCap::new());
+        t.fire(InstallCap::new())
+    }
+}
+
+#[derive(Default)]
+struct Handlers(Vec<Box<dyn Fn() -> u32>>);
+
+#[hazmat::suit]
+impl boxed_handlers::Install for Handlers {
+    fn install(&mut self, handler: Box<dyn Fn() -> u32>) {
+        self.0.push(handler);
+    }
+
+    fn fire(&self) -> u32 {
+        self.0.iter().map(|h| h()).sum()
+    }
+}
+
+#[test]
+fn boxed_trait_object_arguments() {
+    let mut handlers = Handlers::default();
+    assert_eq!(boxed_handlers::call(&mut handlers, 3), 6);
+    assert_eq!(boxed_handlers::call(&mut handlers, 4), 14);
+}
+
+mod custom_results {
+    /// A crate-specific result wrapper.
+    #[derive(Debug, PartialEq)]
+    pub struct MyResult<T>(pub Option<T>);
+
+    #[hazmat::suit]
+    pub trait Op {
+        fn op(&self) -> MyResult<Self>
+        where
+            Self: Sized;
+    }
+
+    pub fn call<T: Op>(t: &T) -> MyResult<T> {
+        t.op(OpCap::new())
+    }
+}
+
+#[hazmat::suit]
+impl custom_results::Op for Sum {
+    fn op(&self) -> custom_results::MyResult<Self>
+    where
+        Self: Sized,
+    {
+        custom_results::MyResult(self.0.checked_mul(2).map(Sum))
+    }
+}
+
+#[test]
+fn custom_result_wrapped_self_returns() {
+    use custom_results::MyResult;
+
+    assert_eq!(custom_results::call(&Sum(2)), MyResult(Some(Sum(4))));
+    assert_eq!(custom_results::call(&Sum(u32::MAX)), MyResult(None));
+}
+
+mod impl_into_args {
+    #[hazmat::suit]
+    pub trait Named {
+        fn named(&mut self, name: impl Into<String>);
+
+        fn name(&self) -> &str;
+    }
+
+    pub fn call<T: Named>(t: &mut T) -> String {
+        t.named("str", NamedCap::new());
+        let first = t.name(NamedCap::new()).to_owned();
+        t.named(String::from("string"), NamedCap::new());
+        first + " " + t.name(NamedCap::new())
+    }
+}
+
+#[derive(Default)]
+struct Label(String);
+
+#[hazmat::suit]
+impl impl_into_args::Named for Label {
+    fn named(&mut self, name: impl Into<String>) {
+        self.0 = name.into();
+    }
+
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+#[test]
+fn impl_into_arguments() {
+    assert_eq!(impl_into_args::call(&mut Label::default()), "str string");
+}
+
+mod const_generic_array_returns {
+    #[hazmat::suit]
+    pub trait Hash<const N: usize> {
+        fn block(&self) -> &[u8; N];
+    }
+
+    pub fn call<T: Hash<4>>(t: &T) -> &[u8; 4] {
+        t.block(HashCap::new())
+    }
+}
+
+struct Digest([u8; 4]);
+
+#[hazmat::suit]
+impl const_generic_array_returns::Hash<4> for Digest {
+    fn block(&self) -> &[u8; 4] {
+        &self.0
+    }
+}
+
+#[test]
+fn const_generic_array_reference_returns() {
+    let digest = Digest([1, 2, 3, 4]);
+    assert_eq!(const_generic_array_returns::call(&digest), &[1, 2, 3, 4]);
+
+    // The returned reference borrows from `self`.
+    let _block: for<'a> fn(&'a Digest, const_generic_array_returns::HashCap) -> &'a [u8; 4] =
+        <Digest as const_generic_array_returns::Hash<4>>::block;
+}
+
+mod poll_io {
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    #[hazmat::suit]
+    pub trait RawWrite {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>>;
+    }
+
+    pub fn call<T: RawWrite + Unpin>(t: &mut T, buf: &[u8]) -> io::Result<usize> {
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        match Pin::new(t).poll_write(&mut cx, buf, RawWriteCap) {
+            Poll::Ready(res) => res,
+            Poll::Pending => unreachable!(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Sink(Vec<u8>);
+
+#[hazmat::suit]
+impl poll_io::RawWrite for Sink {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        if buf.is_empty() {
+            return std::task::Poll::Ready(Err(std::io::ErrorKind::WriteZero.into()));
+        }
+        let len = buf.len().min(2);
+        self.get_mut().0.extend_from_slice(&buf[..len]);
+        std::task::Poll::Ready(Ok(len))
+    }
+}
+
+#[test]
+fn poll_based_io() {
+    let mut sink = Sink::default();
+    assert_eq!(poll_io::call(&mut sink, b"abc").unwrap(), 2);
+    assert_eq!(sink.0, b"ab");
+    assert!(poll_io::call(&mut sink, b"").is_err());
+
+    // The capability is appended after `buf`, and the pinned receiver is kept.
+    use std::{pin::Pin, task::Context};
+    type PollWrite = std::task::Poll<std::io::Result<usize>>;
+    let _poll_write: fn(
+        Pin<&mut Sink>,
+        &mut Context<'_>,
+        &[u8],
+        poll_io::RawWriteCap,
+    ) -> PollWrite = <Sink as poll_io::RawWrite>::poll_write;
+}
+
+mod converted_errors {
+    #[derive(Debug, PartialEq)]
+    pub struct Overflow;
+
+    #[derive(Debug, PartialEq)]
+    pub enum AppError {
+        Overflow,
+    }
+
+    impl From<Overflow> for AppError {
+        fn from(_: Overflow) -> Self {
+            AppError::Overflow
+        }
+    }
+
+    #[hazmat::suit]
+    pub trait Validate {
+        type Error;
+
+        fn validate<E: From<Self::Error>>(&self) -> Result<(), E>;
+    }
+
+    pub fn call<T: Validate<Error = Overflow>>(t: &T) -> Result<(), AppError> {
+        t.validate::<AppError>(ValidateCap::new())
+    }
+}
+
+#[hazmat::suit]
+impl converted_errors::Validate for Sum {
+    type Error = converted_errors::Overflow;
+
+    fn validate<E: From<Self::Error>>(&self) -> Result<(), E> {
+        match self.0.checked_mul(2) {
+            Some(_) => Ok(()),
+            None => Err(converted_errors::Overflow.into()),
+        }
+    }
+}
+
+#[test]
+fn generic_errors_converted_from_assoc_types() {
+    use converted_errors::{AppError, Overflow};
+
+    assert_eq!(converted_errors::call(&Sum(2)), Ok(()));
+    assert_eq!(
+        converted_errors::call(&Sum(u32::MAX)),
+        Err(AppError::Overflow)
+    );
+
+    // The error can also be the associated type itself.
+    let _validate: fn(&Sum, converted_errors::ValidateCap) -> Result<(), Overflow> =
+        <Sum as converted_errors::Validate>::validate::<Overflow>;
+}
+
+mod uninit_buffers {
+    use core::mem::MaybeUninit;
+
+    #[hazmat::suit]
+    pub trait Fill {
+        fn fill(&self, out: &mut [MaybeUninit<u8>]);
+    }
+
+    pub fn call<T: Fill>(t: &T) -> [u8; 4] {
+        let mut out = [MaybeUninit::uninit(); 4];
+        t.fill(&mut out, FillCap::new());
+        out.map(|b| {
+            // SAFETY: `Ramp::fill` initializes every byte of the buffer.
+            unsafe { b.assume_init() }
+        })
+    }
+}
+
+struct Ramp(u8);
+
+#[hazmat::suit]
+impl uninit_buffers::Fill for Ramp {
+    fn fill(&self, out: &mut [core::mem::MaybeUninit<u8>]) {
+        for (i, b) in out.iter_mut().enumerate() {
+            b.write(self.0 + i as u8);
+        }
+    }
+}
+
+#[test]
+fn maybe_uninit_output_buffers() {
+    assert_eq!(uninit_buffers::call(&Ramp(5)), [5, 6, 7, 8]);
+
+    let _fill: fn(&Ramp, &mut [core::mem::MaybeUninit<u8>], uninit_buffers::FillCap) =
+        <Ramp as uninit_buffers::Fill>::fill;
+}
+
+mod sync_async {
+    #[hazmat::suit]
+    pub trait Backend {
+        async fn concurrent_op(&self) -> u32
+        where
+            Self: Sync;
+
+        async fn concurrent_twice(&self) -> u32
+        where
+            Self: Sync,
+        {
+            // `&Self` is `Send` because `Self: Sync`, so the other half can run on a
+            // different thread.
+            let other = std::thread::scope(|s| {
+                s.spawn(|| crate::block_on(self.concurrent_op(BackendCap::new())))
+                    .join()
+                    .unwrap()
+            });
+            self.concurrent_op(cap).await + other
+        }
+    }
+
+    pub async fn call(t: &crate::Fallible) -> u32 {
+        t.concurrent_twice(BackendCap::new()).await
+    }
+}
+
+#[hazmat::suit]
+impl sync_async::Backend for Fallible {
+    async fn concurrent_op(&self) -> u32
+    where
+        Self: Sync,
+    {
+        core::future::ready(()).await;
+        u32::from(self.0) + 1
+    }
+}
+
+#[test]
+fn sync_bounded_async_methods() {
+    assert_eq!(block_on(assert_send(sync_async::call(&Fallible(true)))), 4);
+
+    let _op = <Fallible as sync_async::Backend>::concurrent_op(
+        &Fallible(false),
+        sync_async::BackendCap::new(),
+    );
+}
@@ -0,0 +1,54 @@
+mod traits {
+    #[hazmat::suit(safe_ext = "CountExt", verify_arity)]
+    pub trait Count {
+        fn count(&self) -> u32;
+
+        // This helper doesn't touch the primitive, so it doesn't need the capability.
+        #[hazmat::skip]
+        fn dup(&self) -> Self
+        where
+            Self: Clone,
+        {
+            self.clone()
+        }
+
+        #[hazmat::skip]
+        fn describe(&self) -> &'static str {
+            "count"
+        }
+    }
+
+    pub fn call<T: Count + Clone>(t: &T) -> u32 {
+        t.dup().count(CountCap::new())
+    }
+}
+
+#[derive(Clone)]
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Count for Num {
+    fn count(&self) -> u32 {
+        self.0
+    }
+
+    // Overrides of skipped methods are skipped too.
+    #[hazmat::skip]
+    fn describe(&self) -> &'static str {
+        "num"
+    }
+}
+
+#[test]
+fn skipped_methods_need_no_cap() {
+    use traits::{Count, CountExt};
+
+    let num = Num(3);
+    assert_eq!(traits::call(&num), 3);
+    assert_eq!(num.dup().0, 3);
+    assert_eq!(num.describe(), "num");
+    assert_eq!(num.count_safe(), 3);
+
+    // The where-clause is preserved on the skipped method.
+    let _dup: fn(&Num) -> Num = <Num as Count>::dup;
+}
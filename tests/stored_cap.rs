@@ -0,0 +1,48 @@
+//! Tests that capabilities can be held in state and reused.
+
+mod traits {
+    /// A long-lived helper that holds a capability.
+    pub struct Hasher<'a, T: ?Sized> {
+        inner: &'a T,
+        cap: HashCap,
+    }
+
+    impl<T: Hash + ?Sized> Hasher<'_, T> {
+        pub fn hash(&self, data: &[u8]) -> u32 {
+            self.inner.hash(data, &self.cap)
+        }
+    }
+
+    #[hazmat::suit(by_ref)]
+    pub trait Hash {
+        fn hash(&self, data: &[u8]) -> u32;
+
+        // The injected capability is only borrowed, so the default body constructs one
+        // for the helper to hold.
+        fn hasher(&self) -> Hasher<'_, Self> {
+            Hasher {
+                inner: self,
+                cap: HashCap::new(),
+            }
+        }
+    }
+
+    pub fn call<T: Hash>(t: &T) -> [u32; 3] {
+        let hasher = t.hasher(&HashCap::new());
+        [hasher.hash(b"a"), hasher.hash(b"b"), hasher.hash(b"ab")]
+    }
+}
+
+struct Sum;
+
+#[hazmat::suit(by_ref)]
+impl traits::Hash for Sum {
+    fn hash(&self, data: &[u8]) -> u32 {
+        data.iter().map(|&b| u32::from(b)).sum()
+    }
+}
+
+#[test]
+fn cap_held_in_state() {
+    assert_eq!(traits::call(&Sum), [97, 98, 195]);
+}
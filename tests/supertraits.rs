@@ -0,0 +1,40 @@
+//! Tests suited traits used as supertraits elsewhere in the defining crate.
+
+mod traits {
+    #[hazmat::suit]
+    pub trait Raw {
+        fn raw(&self) -> u8;
+    }
+
+    /// A high-level trait whose supertrait is suited.
+    pub trait Hi: Raw {
+        fn hi(&self) -> u8;
+    }
+
+    // Within the defining crate, a `T: Hi` bound is enough to call the suited supertrait
+    // method, because the crate can mint the capability. Downstream callers of this
+    // function can't mint a `RawCap`, so they can only reach `raw` through it.
+    pub fn call<T: Hi>(t: &T) -> u8 {
+        t.raw(RawCap::new()) + t.hi()
+    }
+}
+
+struct Byte(u8);
+
+#[hazmat::suit]
+impl traits::Raw for Byte {
+    fn raw(&self) -> u8 {
+        self.0
+    }
+}
+
+impl traits::Hi for Byte {
+    fn hi(&self) -> u8 {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn supertrait_bounded_generics() {
+    assert_eq!(traits::call(&Byte(3)), 9);
+}
@@ -0,0 +1,75 @@
+#[macro_use]
+mod common;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+mod traits {
+    #[hazmat::suit(thread_local)]
+    pub trait Local {
+        fn local(&self) -> u32;
+    }
+
+    #[hazmat::suit]
+    pub trait Shared {
+        fn shared(&self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn shared_marked(&self) -> u32;
+    }
+
+    pub fn use_local<T: Local>(t: &T) -> u32 {
+        t.local(LocalCap::new())
+    }
+
+    pub fn use_shared<T: Shared + Sync>(t: &T) -> u32 {
+        std::thread::scope(|s| {
+            let a = s.spawn(|| t.shared(SharedCap::new()));
+            let b = s.spawn(|| t.shared_marked(SharedCap::new()));
+            a.join().unwrap() + b.join().unwrap()
+        })
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Local for Num {
+    fn local(&self) -> u32 {
+        self.0
+    }
+}
+
+#[hazmat::suit]
+impl traits::Shared for Num {
+    fn shared(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn shared_marked(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn default_cap_is_send_and_sync() {
+    assert_send_sync::<traits::SharedCap>();
+    assert_send_sync::<traits::SharedCap<u8>>();
+    assert_eq!(traits::use_shared(&Num(7)), 21);
+}
+
+#[test]
+fn thread_local_cap_is_usable() {
+    assert_eq!(traits::use_local(&Num(7)), 7);
+}
+
+#[test]
+fn thread_local_cap_is_not_send_or_sync() {
+    assert_not_impl!(traits::LocalCap: Send);
+    assert_not_impl!(traits::LocalCap: Sync);
+}
+
+#[test]
+fn thread_local_cap_is_zero_sized() {
+    assert_eq!(core::mem::size_of::<traits::LocalCap>(), 0);
+}
@@ -0,0 +1,44 @@
+//! Tests that tool attributes on methods survive augmentation.
+//!
+//! If the `clippy` attributes were dropped, `cargo clippy -- -D warnings` would fail.
+
+mod traits {
+    #[hazmat::suit]
+    pub trait Consume {
+        /// Consumes `self`.
+        #[doc(alias = "eat")]
+        #[doc(alias("take", "finish"))]
+        #[rustfmt::skip]
+        #[allow(clippy::needless_arbitrary_self_type)]
+        fn consume(self: Self, x: u32)   ->   u32
+        where
+            Self: Sized;
+
+        #[allow(clippy::needless_arbitrary_self_type)]
+        #[rustfmt::skip]
+        fn consume_twice(self: Self, x: u32) -> u32 where Self: Sized + Clone {
+            self.clone().consume(x, ConsumeCap::new()) + self.consume(x, cap)
+        }
+    }
+
+    pub fn call<T: Consume + Clone>(t: T, x: u32) -> u32 {
+        t.consume_twice(x, ConsumeCap::new())
+    }
+}
+
+#[derive(Clone)]
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Consume for Num {
+    #[rustfmt::skip]
+    #[allow(clippy::needless_arbitrary_self_type)]
+    fn consume(self: Self, x: u32)   ->   u32 {
+        self.0 + x
+    }
+}
+
+#[test]
+fn tool_attributes_are_preserved() {
+    assert_eq!(traits::call(Num(1), 2), 6);
+}
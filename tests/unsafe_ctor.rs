@@ -0,0 +1,59 @@
+mod traits {
+    #[hazmat::suit(unsafe_ctor, emit_tests)]
+    pub trait Erase {
+        fn erase(&self) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn erase_marked(&self) -> u32;
+    }
+
+    #[hazmat::suit(unsafe_ctor, audit_callback = crate::audit)]
+    pub trait Audited {
+        fn audited(&self) -> u32;
+    }
+
+    pub fn call<T: Erase + Audited>(t: &T) -> u32 {
+        // SAFETY: This module is authorized to erase and audit.
+        let (cap, marked, audited) = unsafe {
+            (
+                EraseCap::new_unchecked(),
+                EraseCap::new_unchecked::<u8>(),
+                AuditedCap::new_unchecked(),
+            )
+        };
+        t.erase(cap) + t.erase_marked(marked) + t.audited(audited)
+    }
+
+    // The constructor stays `const` when no callback needs to run.
+    // SAFETY: Tests are authorized to erase.
+    pub const CONST_CAP: EraseCap = unsafe { EraseCap::new_unchecked() };
+}
+
+fn audit(_cap: &str, _location: &'static core::panic::Location<'static>) {}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Erase for Num {
+    fn erase(&self) -> u32 {
+        self.0
+    }
+
+    #[hazmat::marker(u8)]
+    fn erase_marked(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+#[hazmat::suit]
+impl traits::Audited for Num {
+    fn audited(&self) -> u32 {
+        self.0 * 3
+    }
+}
+
+#[test]
+fn minted_in_unsafe_block() {
+    assert_eq!(traits::call(&Num(1)), 6);
+    assert_eq!(traits::Erase::erase(&Num(4), traits::CONST_CAP), 4);
+}
@@ -0,0 +1,58 @@
+//! Tests that the injected capability argument doesn't trigger unused lints.
+#![deny(unused)]
+
+mod traits {
+    #[hazmat::suit]
+    pub trait Hash {
+        fn hash(&self) -> u32;
+
+        fn hash_twice(&self) -> u32 {
+            self.hash(cap) * 2
+        }
+
+        fn hash_once(&self) -> u32 {
+            self.hash(HashCap::new())
+        }
+    }
+
+    pub fn call<T: Hash>(t: &T) -> u32 {
+        t.hash_twice(HashCap::new()) + t.hash_once(HashCap::new())
+    }
+}
+
+mod sessions {
+    pub struct Session(pub u32);
+
+    #[hazmat::suit(cap_ty = Session, by_ref)]
+    pub trait Transmit {
+        fn transmit(&self, msg: u32) -> u32;
+    }
+
+    pub fn call<T: Transmit>(t: &T, id: u32, msg: u32) -> u32 {
+        t.transmit(msg, &Session(id))
+    }
+}
+
+struct Num(u32);
+
+#[hazmat::suit]
+impl traits::Hash for Num {
+    fn hash(&self) -> u32 {
+        self.0
+    }
+}
+
+#[hazmat::suit(cap_ty = sessions::Session, by_ref)]
+impl sessions::Transmit for Num {
+    fn transmit(&self, msg: u32) -> u32 {
+        // The capability can be used within macro invocations.
+        assert_eq!(cap.0, 7);
+        self.0 + msg
+    }
+}
+
+#[test]
+fn strict_impls_compile() {
+    assert_eq!(traits::call(&Num(2)), 6);
+    assert_eq!(sessions::call(&Num(2), 7, 3), 5);
+}
@@ -0,0 +1,127 @@
+mod traits {
+    use std::future::Future;
+
+    // The checks name each method through an implementation of the trait, so they must
+    // account for the trait's generics and the bounds of the trait and method.
+    #[hazmat::suit(verify_arity, generic_cap)]
+    pub trait Shaped<'a, T: Copy, const N: usize>: Sized
+    where
+        Self: Send,
+    {
+        fn borrowed<'b>(&'b self, key: &'a [T; N]) -> &'b T
+        where
+            Self: 'b;
+        unsafe fn raw(self, t: T) -> Self;
+        fn later(&self) -> impl Future<Output = T> + Send
+        where
+            Self: Sync;
+        // Methods with type parameters aren't checked.
+        fn generic<U: Into<T>>(&self, u: U) -> T;
+    }
+
+    #[hazmat::suit(verify_arity)]
+    pub trait Multi {
+        fn none() -> u32;
+        fn receiver(&self) -> u32;
+        fn args(&self, a: u32, b: u32) -> u32;
+
+        #[hazmat::marker(u8)]
+        fn marked(&self, a: u32) -> u32;
+
+        fn default_body(&self) -> u32 {
+            self.receiver(cap) + Self::none(MultiCap::new())
+        }
+    }
+
+    #[hazmat::suit(verify_arity, position = "after_receiver", by_ref)]
+    pub trait MultiRef {
+        fn args_ref(&self, a: u32, b: u32) -> u32;
+    }
+
+    #[hazmat::suit(verify_arity, ambient)]
+    pub trait MultiAmbient {
+        fn ambient(&self, a: u32) -> u32;
+    }
+
+    pub fn call<T: Multi + MultiRef + MultiAmbient>(t: &T) -> u32 {
+        t.default_body(MultiCap::new())
+            + t.args(1, 2, MultiCap::new())
+            + t.marked(3, MultiCap::new())
+            + t.args_ref(&MultiRefCap::new(), 4, 5)
+            + MultiAmbientCap::with_capability(|| t.ambient(6))
+    }
+}
+
+struct Num;
+
+#[hazmat::suit]
+impl traits::Multi for Num {
+    fn none() -> u32 {
+        1
+    }
+
+    fn receiver(&self) -> u32 {
+        2
+    }
+
+    fn args(&self, a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    #[hazmat::marker(u8)]
+    fn marked(&self, a: u32) -> u32 {
+        a
+    }
+}
+
+#[hazmat::suit(position = "after_receiver", by_ref)]
+impl traits::MultiRef for Num {
+    fn args_ref(&self, a: u32, b: u32) -> u32 {
+        a * b
+    }
+}
+
+#[hazmat::suit(ambient)]
+impl traits::MultiAmbient for Num {
+    fn ambient(&self, a: u32) -> u32 {
+        a
+    }
+}
+
+#[hazmat::suit(generic_cap)]
+impl<'a> traits::Shaped<'a, u8, 2> for Num {
+    fn borrowed<'b>(&'b self, key: &'a [u8; 2]) -> &'b u8
+    where
+        Self: 'b,
+    {
+        &[0, 1][usize::from(key[0] != 0)]
+    }
+
+    unsafe fn raw(self, _t: u8) -> Self {
+        self
+    }
+
+    async fn later(&self) -> u8
+    where
+        Self: Sync,
+    {
+        7
+    }
+
+    fn generic<U: Into<u8>>(&self, u: U) -> u8 {
+        u.into()
+    }
+}
+
+#[test]
+fn arity_checks_pass() {
+    assert_eq!(traits::call(&Num), 3 + 3 + 3 + 20 + 6);
+}
+
+#[test]
+fn arity_checks_pass_for_generic_traits() {
+    use traits::{Shaped, ShapedCap};
+
+    assert_eq!(*Num.borrowed(&[1, 0], ShapedCap::new()), 1);
+    assert_eq!(Num.generic(3u8, ShapedCap::new()), 3);
+}